@@ -0,0 +1,351 @@
+use crate::{
+    gamepad::GamepadInput,
+    settings::{Bindings, ControlScheme, Settings},
+    touch::TouchControls,
+    ui,
+};
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem, utils::HashSet, window::PrimaryWindow};
+use serde::{Deserialize, Serialize};
+use std::{hash::Hash, ops::Deref};
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActionState>();
+        app.add_systems(
+            PreUpdate,
+            update
+                .in_set(UpdateActions)
+                .after(InputSystem)
+                .after(UiSystem::Focus),
+        );
+    }
+}
+
+/// Updates the [`ActionState`] from this frame's input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct UpdateActions;
+
+/// Everything the player can do, independent of the key or button that is bound to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Throttle,
+    Brake,
+    SteerLeft,
+    SteerRight,
+    Fire,
+    Pause,
+    Quit,
+    Mute,
+    Fullscreen,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Throttle,
+        Action::Brake,
+        Action::SteerLeft,
+        Action::SteerRight,
+        Action::Fire,
+        Action::Pause,
+        Action::Quit,
+        Action::Mute,
+        Action::Fullscreen,
+    ];
+
+    /// The actions controlling a space ship, the only ones bound for the second player.
+    pub const SHIP: [Action; 5] = [
+        Action::Throttle,
+        Action::Brake,
+        Action::SteerLeft,
+        Action::SteerRight,
+        Action::Fire,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Throttle => "Throttle",
+            Action::Brake => "Brake",
+            Action::SteerLeft => "Steer Left",
+            Action::SteerRight => "Steer Right",
+            Action::Fire => "Fire",
+            Action::Pause => "Pause",
+            Action::Quit => "Quit",
+            Action::Mute => "Mute",
+            Action::Fullscreen => "Fullscreen",
+        }
+    }
+
+    /// Gamepad buttons are not rebindable. Steering also uses the left stick.
+    fn gamepad_button(self) -> Option<GamepadButtonType> {
+        match self {
+            Action::Throttle => Some(GamepadButtonType::RightTrigger2),
+            Action::Brake => Some(GamepadButtonType::LeftTrigger2),
+            Action::SteerLeft => Some(GamepadButtonType::DPadLeft),
+            Action::SteerRight => Some(GamepadButtonType::DPadRight),
+            Action::Fire => Some(GamepadButtonType::South),
+            Action::Pause => Some(GamepadButtonType::Start),
+            Action::Quit => Some(GamepadButtonType::Select),
+            Action::Mute | Action::Fullscreen => None,
+        }
+    }
+}
+
+/// The actions of one player, or of all input devices combined.
+#[derive(Debug, Default)]
+pub struct PlayerActions {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    steer: f32,
+    throttle: f32,
+    aim: Option<Vec2>,
+}
+
+impl PlayerActions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// From -1 (full left) to 1 (full right). Keys steer with full deflection.
+    pub fn steer(&self) -> f32 {
+        self.steer
+    }
+
+    /// From 0 to 1. Keys give full thrust.
+    pub fn throttle(&self) -> f32 {
+        self.throttle
+    }
+
+    /// The world position to turn toward, with the [`ControlScheme::MouseAim`] control scheme.
+    pub fn aim(&self) -> Option<Vec2> {
+        self.aim
+    }
+}
+
+/// The actions pressed this frame, from the keyboard (see [`crate::settings::Bindings`]), all
+/// gamepads and the touch controls.
+///
+/// Dereferences to the actions of all devices combined. In co-op, [`ActionState::player`] splits
+/// the devices between the players.
+#[derive(Debug, Default, Resource)]
+pub struct ActionState {
+    all: PlayerActions,
+    players: [PlayerActions; 2],
+}
+
+impl ActionState {
+    /// The first player uses the primary key bindings, the mouse, touch and the first gamepad.
+    /// The second player uses [`crate::settings::Settings::player_two_bindings`] and the second
+    /// gamepad.
+    pub fn player(&self, index: usize) -> &PlayerActions {
+        &self.players[index.min(self.players.len() - 1)]
+    }
+}
+
+impl Deref for ActionState {
+    type Target = PlayerActions;
+
+    fn deref(&self) -> &Self::Target {
+        &self.all
+    }
+}
+
+/// Buttons pressed while they are meant for something else, e.g. the key that was just bound
+/// (or ESC to cancel binding it) or a click on a UI button. They are ignored until released, so
+/// they do not trigger their action right after.
+#[derive(Debug)]
+struct IgnoredUntilReleased<T>(HashSet<T>);
+
+impl<T> Default for IgnoredUntilReleased<T> {
+    fn default() -> Self {
+        Self(HashSet::default())
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> IgnoredUntilReleased<T> {
+    /// Ignores all pressed buttons while `ignore` is set.
+    fn update(&mut self, ignore: bool, input: &ButtonInput<T>) {
+        if ignore {
+            self.0.extend(input.get_pressed().copied());
+        }
+        self.0.retain(|button| input.pressed(*button));
+    }
+
+    fn pressed(&self, button: T, input: &ButtonInput<T>) -> bool {
+        input.pressed(button) && !self.0.contains(&button)
+    }
+}
+
+/// The input devices controlling one [`PlayerActions`].
+struct Devices<'a> {
+    bindings: &'a Bindings,
+    gamepad_pressed: &'a dyn Fn(GamepadButtonType) -> bool,
+    trigger: f32,
+    stick: Vec2,
+    /// The mouse (with [`ControlScheme::MouseAim`]) and the touch controls.
+    pointer: bool,
+}
+
+fn update(
+    mut action_state: ResMut<ActionState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    gamepad: GamepadInput,
+    touch: Res<TouchControls>,
+    settings: Res<Settings>,
+    primary_window: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    keyboard_captured: Query<(), With<ui::CapturesKeyboard>>,
+    interactions: Query<&Interaction>,
+    mut ignored_keys: Local<IgnoredUntilReleased<KeyCode>>,
+    mut ignored_mouse_buttons: Local<IgnoredUntilReleased<MouseButton>>,
+) {
+    // Typing a name or a key to bind must not trigger actions, and neither must clicking a button.
+    ignored_keys.update(!keyboard_captured.is_empty(), &keyboard_input);
+    let over_ui = interactions
+        .iter()
+        .any(|interaction| *interaction != Interaction::None);
+    ignored_mouse_buttons.update(over_ui, &mouse_input);
+    let mouse_pressed = |button| ignored_mouse_buttons.pressed(button, &mouse_input);
+    let mouse_aim = settings.control_scheme == ControlScheme::MouseAim;
+    let aim = mouse_aim
+        .then(|| {
+            let cursor_position = primary_window.get_single().ok()?.cursor_position()?;
+            let (camera, camera_transform) = cameras.get_single().ok()?;
+            camera.viewport_to_world_2d(camera_transform, cursor_position)
+        })
+        .flatten();
+
+    let read = |devices: &Devices, previous: &PlayerActions| {
+        let key_pressed = |action| {
+            devices
+                .bindings
+                .get(action)
+                .0
+                .iter()
+                .any(|key| ignored_keys.pressed(*key, &keyboard_input))
+        };
+        let button_pressed = |action: Action| {
+            action
+                .gamepad_button()
+                .is_some_and(|button| (devices.gamepad_pressed)(button))
+        };
+        let pointer_pressed = |action| {
+            devices.pointer
+                && match action {
+                    Action::Fire => touch.fire || mouse_aim && mouse_pressed(MouseButton::Left),
+                    Action::Throttle => mouse_aim && mouse_pressed(MouseButton::Right),
+                    Action::Brake => touch.brake,
+                    _ => false,
+                }
+        };
+
+        let mut pressed = HashSet::new();
+        for action in Action::ALL {
+            let stick_pressed = match action {
+                Action::SteerLeft => devices.stick.x < -0.5,
+                Action::SteerRight => devices.stick.x > 0.5,
+                _ => false,
+            };
+            if key_pressed(action)
+                || button_pressed(action)
+                || pointer_pressed(action)
+                || stick_pressed
+            {
+                pressed.insert(action);
+            }
+        }
+
+        // Keys and the d-pad give full deflection, the sticks and trigger are analog.
+        let touch_steer = if devices.pointer { touch.steer() } else { 0.0 };
+        let touch_throttle = if devices.pointer {
+            touch.throttle()
+        } else {
+            0.0
+        };
+        let digital_steer = |action| key_pressed(action) || button_pressed(action);
+        let steer = match (
+            digital_steer(Action::SteerLeft),
+            digital_steer(Action::SteerRight),
+        ) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => (devices.stick.x + touch_steer).clamp(-1.0, 1.0),
+        };
+        let throttle = if key_pressed(Action::Throttle) || pointer_pressed(Action::Throttle) {
+            1.0
+        } else {
+            devices.trigger.max(touch_throttle)
+        };
+
+        PlayerActions {
+            just_pressed: pressed.difference(&previous.pressed).copied().collect(),
+            pressed,
+            steer,
+            throttle,
+            // Steering with keys, the d-pad or a stick takes over from aiming while it lasts.
+            aim: aim.filter(|_| devices.pointer && steer == 0.0),
+        }
+    };
+
+    action_state.all = read(
+        &Devices {
+            bindings: &settings.bindings,
+            gamepad_pressed: &|button| gamepad.pressed(button),
+            trigger: gamepad.value(GamepadButtonType::RightTrigger2),
+            stick: gamepad.stick(),
+            pointer: true,
+        },
+        &action_state.all,
+    );
+    for index in 0..action_state.players.len() {
+        let player_gamepad = gamepad.get(index);
+        action_state.players[index] = read(
+            &Devices {
+                bindings: settings.bindings(index),
+                gamepad_pressed: &|button| {
+                    player_gamepad
+                        .as_ref()
+                        .is_some_and(|gamepad| gamepad.pressed(button))
+                },
+                trigger: player_gamepad.as_ref().map_or(0.0, |gamepad| {
+                    gamepad.value(GamepadButtonType::RightTrigger2)
+                }),
+                stick: player_gamepad
+                    .as_ref()
+                    .map_or(Vec2::ZERO, |gamepad| gamepad.stick()),
+                pointer: index == 0,
+            },
+            &action_state.players[index],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_key_is_ignored_until_released() {
+        let mut input = ButtonInput::default();
+        let mut ignored_keys = IgnoredUntilReleased::default();
+
+        // Bound while capturing, and still held after the capture ended.
+        input.press(KeyCode::KeyM);
+        ignored_keys.update(true, &input);
+        assert!(!ignored_keys.pressed(KeyCode::KeyM, &input));
+        ignored_keys.update(false, &input);
+        assert!(!ignored_keys.pressed(KeyCode::KeyM, &input));
+
+        input.release(KeyCode::KeyM);
+        ignored_keys.update(false, &input);
+        input.press(KeyCode::KeyM);
+        ignored_keys.update(false, &input);
+        assert!(ignored_keys.pressed(KeyCode::KeyM, &input));
+    }
+}
@@ -1,25 +1,160 @@
-use crate::AssetsState;
+use crate::{game::Level, AssetsState};
 use bevy::{
+    asset::{LoadState, RecursiveDependencyLoadState, UntypedAssetLoadFailedEvent},
+    audio::PlaybackMode,
     ecs::system::RunSystemOnce,
     prelude::*,
     render::{mesh::PrimitiveTopology, render_asset::RenderAssetUsages},
     sprite::Mesh2dHandle,
 };
 use bevy_asset_loader::prelude::*;
+use std::marker::PhantomData;
 
 pub struct GameAssetsPlugin;
 
 impl Plugin for GameAssetsPlugin {
     fn build(&self, app: &mut App) {
-        app.configure_loading_state(
-            LoadingStateConfig::new(AssetsState::Loading)
-                .load_collection::<AudioAssets>()
-                .init_resource::<GameAssets>(),
+        // Required by `AssetCollection::load`, usually added by `bevy_asset_loader`'s loading states.
+        app.init_resource::<DynamicAssets>()
+            .init_resource::<FailedAssets>();
+
+        // The collections are loaded by hand instead of using a `LoadingState` from
+        // `bevy_asset_loader`, because its loading states can not be retried after a failure.
+        app.add_systems(
+            OnEnter(AssetsState::Loading),
+            (
+                reset_failed_assets,
+                start_loading::<LevelAssets>,
+                start_loading::<AudioAssets>.run_if(not(resource_exists::<NoAudio>)),
+                init_silent_audio.run_if(resource_exists::<NoAudio>),
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                check_loading::<LevelAssets>,
+                check_loading::<AudioAssets>,
+                record_failed_assets,
+                finish_loading,
+            )
+                .chain()
+                .run_if(in_state(AssetsState::Loading)),
         );
+
+        app.add_systems(Update, discard_sounds.run_if(resource_exists::<NoAudio>));
     }
 }
 
-#[derive(AssetCollection, Resource)]
+/// Paths and errors of all assets that failed during the last loading attempt.
+#[derive(Debug, Default, Resource)]
+pub struct FailedAssets(pub Vec<String>);
+
+/// Insert this before (re)entering [`AssetsState::Loading`] to continue without loading any audio.
+#[derive(Debug, Resource)]
+pub struct NoAudio;
+
+#[derive(Resource)]
+struct LoadingCollection<C> {
+    handles: Vec<UntypedHandle>,
+    marker: PhantomData<fn() -> C>,
+}
+
+fn reset_failed_assets(mut failed_assets: ResMut<FailedAssets>) {
+    failed_assets.0.clear();
+}
+
+fn start_loading<C: AssetCollection>(world: &mut World) {
+    // Collections that were loaded by a previous attempt are kept.
+    if world.contains_resource::<C>() {
+        return;
+    }
+
+    let handles = C::load(world);
+    world.insert_resource(LoadingCollection::<C> {
+        handles,
+        marker: PhantomData,
+    });
+}
+
+fn check_loading<C: AssetCollection>(world: &mut World) {
+    let Some(loading) = world.get_resource::<LoadingCollection<C>>() else {
+        return;
+    };
+    let asset_server = world.resource::<AssetServer>();
+
+    let failed = loading.handles.iter().any(|handle| {
+        matches!(
+            asset_server.get_load_states(handle.id()),
+            Some((LoadState::Failed(_), _, _) | (_, _, RecursiveDependencyLoadState::Failed))
+        )
+    });
+    let loaded = loading
+        .handles
+        .iter()
+        .all(|handle| asset_server.is_loaded_with_dependencies(handle.id()));
+
+    if failed {
+        world.remove_resource::<LoadingCollection<C>>();
+    } else if loaded {
+        let collection = C::create(world);
+        world.insert_resource(collection);
+        world.remove_resource::<LoadingCollection<C>>();
+    }
+}
+
+fn record_failed_assets(
+    mut events: EventReader<UntypedAssetLoadFailedEvent>,
+    mut failed_assets: ResMut<FailedAssets>,
+) {
+    for event in events.read() {
+        failed_assets
+            .0
+            .push(format!("{}: {}", event.path, event.error));
+    }
+}
+
+fn finish_loading(
+    mut commands: Commands,
+    loading_levels: Option<Res<LoadingCollection<LevelAssets>>>,
+    loading_audio: Option<Res<LoadingCollection<AudioAssets>>>,
+    level_assets: Option<Res<LevelAssets>>,
+    audio_assets: Option<Res<AudioAssets>>,
+    mut next_state: ResMut<NextState<AssetsState>>,
+) {
+    if loading_levels.is_some() || loading_audio.is_some() {
+        return;
+    }
+
+    if level_assets.is_some() && audio_assets.is_some() {
+        commands.init_resource::<GameAssets>();
+        next_state.set(AssetsState::Loaded);
+    } else {
+        next_state.set(AssetsState::Error);
+    }
+}
+
+fn init_silent_audio(mut commands: Commands) {
+    commands.insert_resource(AudioAssets::default());
+}
+
+/// Sounds without a loaded source never start playing, so they would never be despawned either.
+fn discard_sounds(
+    mut commands: Commands,
+    sounds: Query<(Entity, &PlaybackSettings), (With<Handle<AudioSource>>, Without<AudioSink>)>,
+) {
+    for (entity, settings) in &sounds {
+        match settings.mode {
+            PlaybackMode::Despawn => commands.entity(entity).despawn(),
+            PlaybackMode::Remove => {
+                commands.entity(entity).remove::<AudioBundle>();
+            }
+            PlaybackMode::Once | PlaybackMode::Loop => (),
+        }
+    }
+}
+
+/// Without [`NoAudio`] all handles point to loaded sounds, otherwise they are all default handles.
+#[derive(AssetCollection, Resource, Default)]
 #[allow(dead_code)]
 pub struct AudioAssets {
     #[asset(path = "laserSmall_000.ogg")]
@@ -50,6 +185,38 @@ pub struct AudioAssets {
 
 impl AudioAssets {}
 
+#[derive(AssetCollection, Resource)]
+pub struct LevelAssets {
+    #[cfg(not(target_arch = "wasm32"))]
+    #[asset(path = "levels", collection(typed))]
+    levels: Vec<Handle<Level>>,
+
+    // Directories can not be listed on the web, so the shipped levels are listed explicitly.
+    #[cfg(target_arch = "wasm32")]
+    #[asset(
+        paths(
+            "levels/easy.level.ron",
+            "levels/medium.level.ron",
+            "levels/hard.level.ron"
+        ),
+        collection(typed)
+    )]
+    levels: Vec<Handle<Level>>,
+}
+
+impl LevelAssets {
+    /// All loaded levels, ordered by difficulty.
+    pub fn sorted<'a>(&'a self, levels: &'a Assets<Level>) -> Vec<(&'a Handle<Level>, &'a Level)> {
+        let mut sorted = self
+            .levels
+            .iter()
+            .filter_map(|handle| Some((handle, levels.get(handle)?)))
+            .collect::<Vec<_>>();
+        sorted.sort_by(|(_, a), (_, b)| (a.difficulty, &a.name).cmp(&(b.difficulty, &b.name)));
+        sorted
+    }
+}
+
 #[derive(Debug, Resource)]
 pub struct GameAssets {
     pub star_mesh: Mesh2dHandle,
@@ -63,8 +230,9 @@ pub struct GameAssets {
     pub enemy_space_ship_material: Handle<ColorMaterial>,
     pub enemy_bullet_material: Handle<ColorMaterial>,
 
-    pub player_space_ship_material: Handle<ColorMaterial>,
-    pub player_bullet_material: Handle<ColorMaterial>,
+    /// One for each player.
+    pub player_space_ship_materials: [Handle<ColorMaterial>; 2],
+    pub player_bullet_materials: [Handle<ColorMaterial>; 2],
 
     pub home_planet_material: Handle<ColorMaterial>,
     pub background_material: Handle<ColorMaterial>,
@@ -94,8 +262,14 @@ fn load_assets(
         enemy_space_ship_material: materials.add(Color::srgb(1.4, 0.6, 0.6)),
         enemy_bullet_material: materials.add(Color::srgb(2.0, 0.0, 0.0)),
 
-        player_space_ship_material: materials.add(Color::srgb(0.6, 0.6, 1.4)),
-        player_bullet_material: materials.add(Color::srgb(0.0, 0.0, 2.0)),
+        player_space_ship_materials: [
+            materials.add(Color::srgb(0.6, 0.6, 1.4)),
+            materials.add(Color::srgb(1.4, 1.2, 0.4)),
+        ],
+        player_bullet_materials: [
+            materials.add(Color::srgb(0.0, 0.0, 2.0)),
+            materials.add(Color::srgb(2.0, 1.5, 0.0)),
+        ],
 
         home_planet_material: materials.add(Color::srgb(0.2, 0.5, 2.0)),
         background_material: materials.add(Color::srgb(6.0, 6.0, 6.0)),
@@ -0,0 +1,164 @@
+//! Plays levels many times with bots and different seeds, to see how hard they are.
+
+use crate::{
+    game::{GameMode, GameState, Level, SIMULATION_RATE},
+    headless::Simulation,
+};
+use serde::Serialize;
+use std::ops::Range;
+
+/// How a single run of a level played out, with bots playing all players.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunResult {
+    pub seed: u64,
+    /// `false` if the game was lost or still running after the maximum number of steps.
+    pub won: bool,
+    pub steps: u64,
+    /// Fraction of the home planet's health that is left.
+    pub home_health: f32,
+    pub deaths: u32,
+    pub kills: u32,
+}
+
+impl RunResult {
+    /// Plays a single run with bots, stopping after `max_steps`.
+    pub fn play(level: &Level, mode: GameMode, seed: u64, max_steps: u64) -> Self {
+        let mut simulation = Simulation::new(level.clone(), mode, seed);
+        for player in 0..mode.players() {
+            simulation.add_bot(player);
+        }
+        let steps = simulation.run_until_over(max_steps).unwrap_or(max_steps);
+        let stats = simulation.stats();
+        Self {
+            seed,
+            won: simulation.state() == GameState::GameWon,
+            steps,
+            home_health: stats.home_health,
+            deaths: stats.deaths,
+            kills: stats.kills,
+        }
+    }
+
+    /// Plays a run for each seed, spread over all CPU cores, ordered by seed.
+    pub fn play_many(
+        level: &Level,
+        mode: GameMode,
+        seeds: Range<u64>,
+        max_steps: u64,
+    ) -> Vec<Self> {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let seeds = seeds.collect::<Vec<_>>();
+        let mut results = std::thread::scope(|scope| {
+            let handles = seeds
+                .chunks(seeds.len().div_ceil(threads).max(1))
+                .map(|seeds| {
+                    scope.spawn(move || {
+                        seeds
+                            .iter()
+                            .map(|seed| Self::play(level, mode, *seed, max_steps))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        results.sort_by_key(|result| result.seed);
+        results
+    }
+}
+
+/// The averages of many runs of a level.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelReport {
+    pub level: String,
+    pub runs: usize,
+    pub win_rate: f32,
+    pub average_home_health: f32,
+    pub average_deaths: f32,
+    pub average_kills: f32,
+    /// In seconds.
+    pub average_run_length: f32,
+}
+
+impl LevelReport {
+    pub fn new(level: &str, results: &[RunResult]) -> Self {
+        let average = |value: fn(&RunResult) -> f32| match results.len() {
+            0 => 0.0,
+            runs => results.iter().map(value).sum::<f32>() / runs as f32,
+        };
+        Self {
+            level: level.to_string(),
+            runs: results.len(),
+            win_rate: average(|result| if result.won { 1.0 } else { 0.0 }),
+            average_home_health: average(|result| result.home_health),
+            average_deaths: average(|result| result.deaths as f32),
+            average_kills: average(|result| result.kills as f32),
+            average_run_length: average(|result| (result.steps as f64 / SIMULATION_RATE) as f32),
+        }
+    }
+
+    /// The reports as CSV, with a header line.
+    pub fn to_csv(reports: &[Self]) -> String {
+        let mut csv = "level,runs,win_rate,average_home_health,average_deaths,average_kills,\
+            average_run_length\n"
+            .to_string();
+        for report in reports {
+            csv += &format!(
+                "{},{},{:.3},{:.3},{:.2},{:.2},{:.1}\n",
+                csv_field(&report.level),
+                report.runs,
+                report.win_rate,
+                report.average_home_health,
+                report.average_deaths,
+                report.average_kills,
+                report.average_run_length
+            );
+        }
+        csv
+    }
+}
+
+/// Quotes the field if it contains a separator, a quote or a line break, doubling any quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_runs() {
+        let result = |seed, won, steps, home_health, deaths| RunResult {
+            seed,
+            won,
+            steps,
+            home_health,
+            deaths,
+            kills: 2,
+        };
+        let results = [result(0, true, 1800, 0.5, 1), result(1, false, 600, 0.0, 3)];
+
+        let report = LevelReport::new("Easy", &results);
+        assert_eq!(report.runs, 2);
+        assert_eq!(report.win_rate, 0.5);
+        assert_eq!(report.average_home_health, 0.25);
+        assert_eq!(report.average_deaths, 2.0);
+        assert_eq!(report.average_kills, 2.0);
+        assert_eq!(report.average_run_length, 20.0);
+        let quoted = LevelReport::new("Hard, \"Final\"", &results[..1]);
+        assert_eq!(
+            LevelReport::to_csv(&[report, quoted]),
+            "level,runs,win_rate,average_home_health,average_deaths,average_kills,\
+                average_run_length\nEasy,2,0.500,0.250,2.00,2.00,20.0\n\
+                \"Hard, \"\"Final\"\"\",1,1.000,0.500,1.00,2.00,30.0\n"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{assets::NoAudio, camera::GameCameraBundle, ui, AppState, AssetsState};
+use bevy::prelude::*;
+
+pub struct ErrorScreenPlugin;
+
+impl Plugin for ErrorScreenPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(OnEnter(AppState::Error), setup);
+        app.add_systems(OnExit(AppState::Error), cleanup);
+
+        // Update
+        app.add_systems(Update, update.run_if(in_state(AppState::Error)));
+    }
+}
+
+/// Insert this before switching to [`AppState::Error`].
+#[derive(Debug, Resource)]
+pub struct ErrorScreen {
+    pub title: String,
+    pub details: Vec<String>,
+    pub actions: Vec<ErrorAction>,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+pub enum ErrorAction {
+    MainMenu,
+    RetryLoading,
+    ContinueWithoutAudio,
+}
+
+impl ErrorAction {
+    fn label(&self) -> &'static str {
+        match self {
+            ErrorAction::MainMenu => "Main Menu",
+            ErrorAction::RetryLoading => "Retry",
+            ErrorAction::ContinueWithoutAudio => "Play without Audio",
+        }
+    }
+}
+
+fn update(
+    mut commands: Commands,
+    mut interaction_query: Query<
+        (&Interaction, &ErrorAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_state_assets: ResMut<NextState<AssetsState>>,
+) {
+    for (interaction, action) in &mut interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                ErrorAction::MainMenu => next_state.set(AppState::MainMenu),
+                ErrorAction::RetryLoading => {
+                    next_state.set(AppState::SplashScreen);
+                    next_state_assets.set(AssetsState::Loading);
+                }
+                ErrorAction::ContinueWithoutAudio => {
+                    commands.insert_resource(NoAudio);
+                    next_state.set(AppState::SplashScreen);
+                    next_state_assets.set(AssetsState::Loading);
+                }
+            }
+        }
+    }
+}
+
+fn setup(mut commands: Commands, error_screen: Res<ErrorScreen>) {
+    commands.spawn((GameCameraBundle::default(), StateScoped(AppState::Error)));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(AppState::Error),
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    &error_screen.title,
+                    TextStyle {
+                        font_size: 40.0,
+                        color: Color::srgb(8.0, 0.6, 0.6),
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            for detail in &error_screen.details {
+                parent.spawn(TextBundle::from_section(
+                    detail,
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::srgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ));
+            }
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(20.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for action in &error_screen.actions {
+                        ui::spawn_button_with(parent, action.label(), *action);
+                    }
+                });
+        });
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<ErrorScreen>();
+}
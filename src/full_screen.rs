@@ -1,7 +1,8 @@
-use bevy::{
-    prelude::*,
-    window::{PrimaryWindow, WindowMode},
+use crate::{
+    actions::{Action, ActionState},
+    settings::Settings,
 };
+use bevy::prelude::*;
 
 pub struct FullScreenPlugin;
 
@@ -11,15 +12,8 @@ impl Plugin for FullScreenPlugin {
     }
 }
 
-fn full_screen(
-    mut primary_window: Query<&mut Window, With<PrimaryWindow>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-) {
-    if keyboard_input.just_pressed(KeyCode::F11) {
-        let mut window = primary_window.single_mut();
-        window.mode = match window.mode {
-            WindowMode::Windowed => WindowMode::BorderlessFullscreen,
-            _ => WindowMode::Windowed,
-        };
+fn full_screen(mut settings: ResMut<Settings>, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Fullscreen) {
+        settings.window_mode = settings.window_mode.toggled();
     }
 }
@@ -0,0 +1,116 @@
+use super::{
+    enemy::Enemy, Collider, Home, Planet, Player, PlayerInput, ReadInput, ReplayPlayback,
+    SpaceShip, Star,
+};
+use crate::AppState;
+use bevy::prelude::*;
+
+/// Lets the computer play players with a [`Bot`], e.g. for balance testing.
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            play.in_set(ReadInput)
+                .run_if(in_state(AppState::Game))
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
+    }
+}
+
+/// Sets the [`PlayerInput`] of its player instead of the input devices. Defends the home
+/// planet by hunting the enemy closest to it, or hunts the other player in versus.
+#[derive(Debug, Default, Component)]
+pub struct Bot {
+    /// Seconds until the next shot, to fire about as fast as a person.
+    reload: f32,
+}
+
+/// Seconds between shots.
+const BOT_RELOAD: f32 = 0.25;
+
+/// How close the bot gets to planets and the star before turning away.
+const BOT_SAFETY_DISTANCE: f32 = 60.0;
+
+fn play(
+    time: Res<Time>,
+    mut bots: Query<(
+        Entity,
+        &mut Bot,
+        &Transform,
+        &SpaceShip,
+        &Collider,
+        &mut PlayerInput,
+    )>,
+    players: Query<(Entity, &Transform), With<Player>>,
+    enemies: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    homes: Query<&Transform, (With<Home>, Without<Player>)>,
+    planets_and_stars: Query<
+        (&Transform, &Collider),
+        (Without<Player>, Or<(With<Planet>, With<Star>)>),
+    >,
+) {
+    for (entity, mut bot, transform, space_ship, collider, mut input) in &mut bots {
+        bot.reload -= time.delta_seconds();
+        let position = transform.translation;
+
+        let obstacle = planets_and_stars
+            .iter()
+            .find(|(obj_transform, obj_collider)| {
+                Vec3::distance(position, obj_transform.translation)
+                    < collider.radius + obj_collider.radius + BOT_SAFETY_DISTANCE
+            });
+        let nearest = |targets: &mut dyn Iterator<Item = Vec3>, to: Vec3| {
+            targets.min_by(|a, b| a.distance_squared(to).total_cmp(&b.distance_squared(to)))
+        };
+        let (target, attack) = match (obstacle, homes.get_single()) {
+            // Flies away from the obstacle, without shooting at it.
+            (Some((obj_transform, _)), _) => {
+                let away = (position - obj_transform.translation).normalize_or_zero();
+                (Some(position + away * 200.0), false)
+            }
+            (None, Ok(home)) => (
+                nearest(
+                    &mut enemies.iter().map(|enemy| enemy.translation),
+                    home.translation,
+                ),
+                true,
+            ),
+            (None, Err(_)) => (
+                nearest(
+                    &mut players
+                        .iter()
+                        .filter(|(other, _)| *other != entity)
+                        .map(|(_, other)| other.translation),
+                    position,
+                ),
+                true,
+            ),
+        };
+        let Some(target) = target.filter(|target| *target != position) else {
+            *input = PlayerInput {
+                brake: true,
+                ..default()
+            };
+            continue;
+        };
+
+        let direction = target - position;
+        let distance = direction.length();
+        let angle_between = space_ship.angle_to(direction);
+
+        let fire = attack && distance < 300.0 && angle_between.abs() < 10.0 && bot.reload <= 0.0;
+        *input = PlayerInput {
+            steer: space_ship.steer_toward(direction),
+            throttle: if distance > 150.0 { 1.0 } else { 0.0 },
+            brake: distance < 75.0,
+            // Every press fires once, so fire has to be released in between.
+            fire: fire && !input.fire,
+        };
+        if input.fire {
+            bot.reload = BOT_RELOAD;
+        }
+    }
+}
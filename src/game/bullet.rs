@@ -1,31 +1,49 @@
 use super::{
-    ApplyVelocity, Collider, ExplosionKind, GameState, GravityMultiplier, Health, SpawnExplosion,
-    Velocity,
+    playing, Appearance, ApplyVelocity, Collider, ExplosionKind, GameState, GravityMultiplier,
+    Health, Interpolated, SpawnExplosion, UpdateSpaceShip, Velocity,
 };
-use crate::{assets::GameAssets, AppState};
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use crate::AppState;
+use bevy::prelude::*;
 
 pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        app.add_event::<BulletHit>();
+
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             update
+                .in_set(UpdateBullets)
+                .after(UpdateSpaceShip)
                 .before(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
         );
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct UpdateBullets;
+
 #[derive(Debug, Component)]
 pub struct BulletMissileLock {
     pub target: Entity,
 }
 
+/// Sent whenever a bullet damages something.
+#[derive(Debug, Event)]
+pub struct BulletHit {
+    pub shooter: Entity,
+    pub target: Entity,
+    pub damage: f32,
+    /// Whether this hit reduced the target's health to zero.
+    pub killed: bool,
+}
+
 #[derive(Debug, Component)]
 pub struct Bullet {
+    pub shooter: Entity,
     pub collider_filter: u32,
     pub damage: f32,
     pub time_to_live: f32,
@@ -36,35 +54,35 @@ pub struct BulletBundle {
     pub bullet: Bullet,
     pub velocity: Velocity,
     pub gravity_multiplier: GravityMultiplier,
-    pub mesh: MaterialMesh2dBundle<ColorMaterial>,
+    pub transform: TransformBundle,
+    pub interpolated: Interpolated,
+    pub appearance: Appearance,
 }
 
 impl BulletBundle {
     pub fn new(
+        shooter: Entity,
         collider_filter: u32,
         damage: f32,
         time_to_live: f32,
         velocity: Velocity,
         position: Vec3,
-        material: Handle<ColorMaterial>,
-        assets: &GameAssets,
+        appearance: Appearance,
     ) -> Self {
         Self {
             bullet: Bullet {
+                shooter,
                 collider_filter,
                 damage,
                 time_to_live,
             },
             velocity,
             gravity_multiplier: GravityMultiplier(10.0),
-            mesh: MaterialMesh2dBundle {
-                mesh: assets.bullet_mesh.clone(),
-                material,
-                transform: Transform::from_translation(position)
-                    .with_rotation(rot_from_velocity(*velocity)),
-
-                ..default()
-            },
+            transform: TransformBundle::from_transform(
+                Transform::from_translation(position).with_rotation(rot_from_velocity(*velocity)),
+            ),
+            interpolated: Interpolated::default(),
+            appearance,
         }
     }
 }
@@ -76,20 +94,25 @@ fn rot_from_velocity(velocity: Vec3) -> Quat {
 fn update(
     time: Res<Time>,
     game_state: Res<State<GameState>>,
+    next_state_game: Res<NextState<GameState>>,
     mut bullets: Query<(
         Entity,
         &mut Bullet,
         Option<&BulletMissileLock>,
         &mut Velocity,
         &mut Transform,
-        &Handle<ColorMaterial>,
+        &Appearance,
     )>,
-    mut objects: Query<(&Transform, &Collider, Option<&mut Health>), Without<Bullet>>,
+    mut objects: Query<(Entity, &Transform, &Collider, Option<&mut Health>), Without<Bullet>>,
     targets: Query<&Transform, Without<Bullet>>,
     mut commands: Commands,
     mut explosions: EventWriter<SpawnExplosion>,
+    mut hits: EventWriter<BulletHit>,
 ) {
-    for (entity, mut bullet, lock, mut velocity, mut transform, material) in &mut bullets {
+    // Bullets only do damage until the game is over.
+    let damage = playing(game_state, next_state_game);
+
+    for (entity, mut bullet, lock, mut velocity, mut transform, appearance) in &mut bullets {
         bullet.time_to_live -= time.delta_seconds();
         if let Some(lock) = lock {
             if let Ok(target) = targets.get(lock.target) {
@@ -106,19 +129,26 @@ fn update(
 
         let mut despawn = transform.translation.length() > 1024.0 || bullet.time_to_live <= 0.0;
 
-        for (obj_transform, obj_collider, obj_health) in &mut objects {
+        for (obj_entity, obj_transform, obj_collider, obj_health) in &mut objects {
             if obj_collider.group & bullet.collider_filter == 0 {
                 continue;
             }
             if Vec3::distance_squared(transform.translation, obj_transform.translation)
                 <= obj_collider.radius * obj_collider.radius
             {
-                if **game_state == GameState::Running {
+                if damage {
                     if let Some(mut health) = obj_health {
                         let spawn_explosion = if !(obj_collider.group & 0b100 != 0
                             && bullet.collider_filter & 0b1 == 0)
                         {
+                            let alive = health.current() > 0.0;
                             health.damage(bullet.damage);
+                            hits.send(BulletHit {
+                                shooter: bullet.shooter,
+                                target: obj_entity,
+                                damage: bullet.damage,
+                                killed: alive && health.current() <= 0.0,
+                            });
                             health.current() > 0.0
                         } else {
                             false
@@ -128,7 +158,7 @@ fn update(
                             explosions.send(SpawnExplosion {
                                 position: (transform.translation + obj_transform.translation) / 2.0
                                     + Vec3::Z,
-                                material: material.clone(),
+                                appearance: *appearance,
                                 kind: ExplosionKind::Small,
                             });
                         }
@@ -0,0 +1,313 @@
+use super::{
+    start_level,
+    stats::{format_time, RunStats},
+    versus::VersusScore,
+    GameMode, GameState, Level, ReplayPlayback, Seed,
+};
+use crate::{
+    assets::LevelAssets,
+    high_scores::{HighScoreEntry, HighScores},
+    ui::{self, TextInputSubmitted},
+    AppState,
+};
+use bevy::prelude::*;
+
+pub struct EndScreenPlugin;
+
+impl Plugin for EndScreenPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup
+        app.add_systems(OnEnter(GameState::GameWon), setup_won);
+        app.add_systems(OnEnter(GameState::GameOver), setup_over);
+
+        // Update
+        app.add_systems(
+            Update,
+            (update, submit_name)
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(GameState::GameWon).or_else(in_state(GameState::GameOver))),
+        );
+    }
+}
+
+#[derive(Debug, Component)]
+enum ButtonAction {
+    Retry,
+    Next(Handle<Level>),
+    MainMenu,
+}
+
+/// Container of the name entry shown for a new high score.
+#[derive(Debug, Component)]
+struct NameEntry;
+
+#[derive(Debug, Component)]
+struct NameInput;
+
+fn update(
+    mut commands: Commands,
+    interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
+    levels: Res<Assets<Level>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                ButtonAction::Retry => next_state.set(AppState::StartGame),
+                ButtonAction::Next(handle) => {
+                    if let Some(level) = levels.get(handle) {
+                        start_level(&mut commands, level, &mut next_state);
+                    }
+                }
+                ButtonAction::MainMenu => next_state.set(AppState::MainMenu),
+            }
+        }
+    }
+}
+
+fn submit_name(
+    mut commands: Commands,
+    mut events: EventReader<TextInputSubmitted>,
+    name_inputs: Query<(), With<NameInput>>,
+    name_entries: Query<Entity, With<NameEntry>>,
+    stats: Res<RunStats>,
+    level: Res<Level>,
+    mode: Res<GameMode>,
+    mut high_scores: ResMut<HighScores>,
+) {
+    for event in events.read() {
+        if !name_inputs.contains(event.entity) {
+            continue;
+        }
+
+        let name = event.value.trim();
+        let name = if name.is_empty() { "Anonymous" } else { name };
+        high_scores.insert(
+            &mode.high_score_table(&level.name),
+            HighScoreEntry {
+                name: name.to_string(),
+                score: stats.score(),
+                kills: stats.kills,
+                time_survived: stats.time_survived,
+                home_health: stats.home_health,
+            },
+        );
+
+        for entity in &name_entries {
+            commands
+                .entity(entity)
+                .despawn_descendants()
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        format!("Saved as {name}"),
+                        TextStyle {
+                            font_size: 24.0,
+                            color: Color::srgb(0.9, 0.9, 0.9),
+                            ..default()
+                        },
+                    ));
+                });
+        }
+    }
+}
+
+fn setup_won(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    seed: Res<Seed>,
+    level: Res<Level>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    spawn_end_screen(
+        &mut commands,
+        "YOU WIN!",
+        Color::srgb(0.9, 8.0, 0.9),
+        stats_lines(&stats, &seed),
+        new_high_score(
+            &mode.high_score_table(&level.name),
+            &stats,
+            &high_scores,
+            playback.is_some(),
+        ),
+        next_level(&level, &level_assets, &levels),
+    );
+}
+
+fn setup_over(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    seed: Res<Seed>,
+    level: Res<Level>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+    versus_score: Option<Res<VersusScore>>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    if let Some(score) = versus_score {
+        spawn_end_screen(
+            &mut commands,
+            &format!("PLAYER {} WINS!", score.leader() + 1),
+            Color::srgb(0.9, 8.0, 0.9),
+            vec![
+                format!("Rounds won: {} - {}", score.wins[0], score.wins[1]),
+                format!("Rounds played: {}", score.rounds),
+                format!("Time: {}", format_time(stats.time_survived)),
+                format!("Seed: {}", **seed),
+            ],
+            None,
+            None,
+        );
+        return;
+    }
+
+    spawn_end_screen(
+        &mut commands,
+        "YOU LOSE!",
+        Color::srgb(8.0, 0.6, 0.6),
+        stats_lines(&stats, &seed),
+        new_high_score(
+            &mode.high_score_table(&level.name),
+            &stats,
+            &high_scores,
+            playback.is_some(),
+        ),
+        next_level(&level, &level_assets, &levels),
+    );
+}
+
+/// The name to prefill if the run makes it into the high scores. Watching a replay doesn't
+/// count as a run.
+fn new_high_score(
+    table: &str,
+    stats: &RunStats,
+    high_scores: &HighScores,
+    replay: bool,
+) -> Option<String> {
+    (!replay && high_scores.qualifies(table, stats.score())).then(|| high_scores.last_name.clone())
+}
+
+/// The level following the current one in the main menu order.
+fn next_level(
+    level: &Level,
+    level_assets: &LevelAssets,
+    levels: &Assets<Level>,
+) -> Option<Handle<Level>> {
+    let sorted = level_assets.sorted(levels);
+    let current = sorted
+        .iter()
+        .position(|(_, other)| other.difficulty == level.difficulty && other.name == level.name)?;
+    sorted.get(current + 1).map(|(handle, _)| (*handle).clone())
+}
+
+fn stats_lines(stats: &RunStats, seed: &Seed) -> Vec<String> {
+    vec![
+        format!("Score: {}", stats.score()),
+        format!("Kills: {}", stats.kills),
+        format!(
+            "Accuracy: {:.0}% ({}/{})",
+            stats.accuracy() * 100.0,
+            stats.hits,
+            stats.shots_fired
+        ),
+        format!("Deaths: {}", stats.deaths),
+        format!("Home damage taken: {:.0}", stats.home_damage_taken),
+        format!("Home health: {:.0}%", stats.home_health * 100.0),
+        format!("Time survived: {}", format_time(stats.time_survived)),
+        format!("Seed: {}", **seed),
+    ]
+}
+
+fn spawn_end_screen(
+    commands: &mut Commands,
+    text: &str,
+    color: Color,
+    lines: Vec<String>,
+    high_score_name: Option<String>,
+    next: Option<Handle<Level>>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    text,
+                    TextStyle {
+                        font_size: 80.0,
+                        color,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            for line in lines {
+                parent.spawn(TextBundle::from_section(
+                    line,
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ));
+            }
+            if let Some(name) = high_score_name {
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                margin: UiRect::top(Val::Px(20.0)),
+                                align_items: AlignItems::Center,
+                                flex_direction: FlexDirection::Column,
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        NameEntry,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            "New high score! Enter your name:",
+                            TextStyle {
+                                font_size: 24.0,
+                                color: Color::srgb(0.9, 8.0, 0.9),
+                                ..default()
+                            },
+                        ));
+                        ui::spawn_text_input_with(parent, name, 12, NameInput);
+                    });
+            }
+            parent.spawn(NodeBundle {
+                style: Style {
+                    height: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            });
+            ui::spawn_button_with(parent, "Retry", ButtonAction::Retry);
+            if let Some(next) = next {
+                ui::spawn_button_with(parent, "Next Difficulty", ButtonAction::Next(next));
+            }
+            ui::spawn_button_with(parent, "Main Menu", ButtonAction::MainMenu);
+        });
+}
@@ -1,11 +1,9 @@
 use super::{
-    ApplyVelocity, Collider, ExplosionKind, GameState, Health, Home, Level, Planet, Player,
-    SpaceShip, SpaceShipBundle, SpawnExplosion, Star, Steering, UpdateSpaceShip, Velocity,
-};
-use crate::{
-    assets::{AudioAssets, GameAssets},
-    AppState,
+    defends_home, playing, Appearance, ApplyVelocity, Collider, ExplosionKind, GameRng, Health,
+    Home, Level, Planet, Player, ReadInput, SpaceShip, SpaceShipBundle, SpawnExplosion, Star,
+    UpdateSpaceShip, UseGameRng, Velocity,
 };
+use crate::AppState;
 use bevy::prelude::*;
 use rand::Rng;
 
@@ -17,16 +15,19 @@ impl Plugin for EnemyPlugin {
         app.add_systems(OnEnter(AppState::Game), setup);
         app.add_systems(OnExit(AppState::Game), cleanup);
 
-        // Update
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             spawn_enemies
+                .in_set(UseGameRng::SpawnEnemies)
+                .after(ReadInput)
                 .run_if(in_state(AppState::Game))
-                .run_if(in_state(GameState::Running)),
+                .run_if(playing)
+                .run_if(defends_home),
         );
         app.add_systems(
-            Update,
-            (update, despawn_enemies)
+            FixedUpdate,
+            (update.in_set(UseGameRng::UpdateEnemies), despawn_enemies)
                 .before(UpdateSpaceShip)
                 .before(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
@@ -43,7 +44,8 @@ struct EnemySpawner {
 #[derive(Debug, Component)]
 pub struct Enemy {
     target: Option<EnemyTarget>,
-    last_shot: f32,
+    /// Seconds until the next shot.
+    reload: f32,
     damage_multiplier: f32,
 }
 
@@ -51,7 +53,7 @@ impl Enemy {
     pub fn new(damage_multiplier: f32) -> Self {
         Self {
             target: None,
-            last_shot: 0.0,
+            reload: 0.0,
             damage_multiplier,
         }
     }
@@ -59,6 +61,7 @@ impl Enemy {
 
 #[derive(Debug, Clone, Copy)]
 enum EnemyTarget {
+    /// The nearest player.
     Player,
     Home,
 }
@@ -71,14 +74,7 @@ pub struct EnemyBundle {
 }
 
 impl EnemyBundle {
-    pub fn new(
-        position: Vec3,
-        rotation: f32,
-        damage_multiplier: f32,
-        level: &Level,
-        audio_assets: &AudioAssets,
-        assets: &GameAssets,
-    ) -> Self {
+    pub fn new(position: Vec3, rotation: f32, damage_multiplier: f32, level: &Level) -> Self {
         Self {
             enemy: Enemy::new(damage_multiplier),
             health: level.enemy_health,
@@ -87,10 +83,8 @@ impl EnemyBundle {
                 Velocity(Vec3::ZERO),
                 position,
                 rotation,
-                assets.enemy_space_ship_material.clone(),
-                assets.enemy_bullet_material.clone(),
-                audio_assets,
-                assets,
+                Appearance::Enemy,
+                Appearance::EnemyBullet,
             ),
         }
     }
@@ -101,9 +95,8 @@ fn spawn_enemies(
     mut enemy_spawner: ResMut<EnemySpawner>,
     homes: Query<&Planet, With<Home>>,
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
     level: Res<Level>,
-    audio_assets: Res<AudioAssets>,
-    assets: Res<GameAssets>,
 ) {
     let Ok(home) = homes.get_single() else {
         return;
@@ -116,22 +109,20 @@ fn spawn_enemies(
     }
 
     if !enemy_spawner.spawned_final_wave
-        && level.home_orbit_time - home.orbit_progress * level.home_orbit_time < 1.0
+        && home.orbit_time - home.orbit_progress * home.orbit_time < 1.0
     {
         enemy_spawner.spawned_final_wave = true;
         spawn.extend([0.0; 6]);
     }
 
     for damage_multiplier in spawn {
-        let alpha = rand::thread_rng().gen_range(0.0..std::f32::consts::TAU);
+        let alpha = rng.gen_range(0.0..std::f32::consts::TAU);
         commands.spawn((
             EnemyBundle::new(
                 Vec3::new(f32::cos(alpha) * 512.0, f32::sin(alpha) * 512.0, 0.0),
                 alpha + std::f32::consts::FRAC_PI_2,
                 damage_multiplier,
                 &level,
-                &audio_assets,
-                &assets,
             ),
             StateScoped(AppState::Game),
         ));
@@ -140,14 +131,12 @@ fn spawn_enemies(
 
 fn update(
     time: Res<Time>,
+    mut rng: ResMut<GameRng>,
     level: Res<Level>,
     mut enemies: Query<(&Transform, &mut SpaceShip, &mut Enemy), Without<Player>>,
     players: Query<&Transform, With<Player>>,
     homes: Query<(Entity, &Transform), With<Home>>,
 ) {
-    let Ok(player) = players.get_single() else {
-        return;
-    };
     let Ok((home_entity, home)) = homes.get_single() else {
         for (_, mut space_ship, _) in &mut enemies {
             space_ship.stop();
@@ -156,8 +145,18 @@ fn update(
     };
 
     for (transform, mut space_ship, mut enemy) in &mut enemies {
+        enemy.reload -= time.delta_seconds();
+
+        let player = players.iter().min_by(|a, b| {
+            let a = a.translation.distance_squared(transform.translation);
+            let b = b.translation.distance_squared(transform.translation);
+            a.total_cmp(&b)
+        });
+        let Some(player) = player else {
+            continue;
+        };
         let target = *enemy.target.get_or_insert_with(|| {
-            if rand::thread_rng().gen_range(0.0..1.0) < level.enemy_force_to_home_probability {
+            if rng.gen_range(0.0..1.0) < level.enemy_force_to_home_probability {
                 return EnemyTarget::Home;
             }
 
@@ -177,30 +176,25 @@ fn update(
 
         let direction = target_transform.translation - transform.translation;
         let distance = direction.length();
-        let direction = direction.normalize();
-        let angle_between = direction
-            .angle_between(space_ship.rot_quat() * -Vec3::X)
-            .to_degrees()
-            - 90.0;
-
-        space_ship.steering = match angle_between {
-            angle if angle > 2.5 => Steering::Right,
-            angle if angle < -2.5 => Steering::Left,
-            _ => Steering::None,
+        let angle_between = space_ship.angle_to(direction);
+
+        space_ship.steer = space_ship.steer_toward(direction);
+        space_ship.throttle = if distance > throttle_threshold {
+            1.0
+        } else {
+            0.0
         };
-        space_ship.throttle = distance > throttle_threshold;
         space_ship.brake = distance < brake_threshold;
-        space_ship.shoot = (distance < shoot_threshold
-            && angle_between.abs() < 10.0
-            && time.elapsed_seconds() - enemy.last_shot > reload)
-            .then_some(level.enemy_damage * enemy.damage_multiplier);
+        space_ship.shoot =
+            (distance < shoot_threshold && angle_between.abs() < 10.0 && enemy.reload <= 0.0)
+                .then_some(level.enemy_damage * enemy.damage_multiplier);
         space_ship.shoot_missile_lock = match target {
             EnemyTarget::Player => None,
             EnemyTarget::Home => Some(home_entity),
         };
 
         if space_ship.shoot.is_some() {
-            enemy.last_shot = time.elapsed_seconds();
+            enemy.reload = reload;
         }
     }
 }
@@ -208,13 +202,13 @@ fn update(
 fn despawn_enemies(
     mut commands: Commands,
     mut explosions: EventWriter<SpawnExplosion>,
-    enemies: Query<(Entity, &Transform, &Collider, &Health, &SpaceShip), With<Enemy>>,
+    enemies: Query<(Entity, &Transform, &Collider, &Health, &Appearance), With<Enemy>>,
     planets_and_stars: Query<
         (&Transform, &Collider),
         (Without<Enemy>, Or<(With<Planet>, With<Star>)>),
     >,
 ) {
-    for (entity, transform, collider, health, space_ship) in &enemies {
+    for (entity, transform, collider, health, appearance) in &enemies {
         let mut despawn = health.current() <= 0.0;
 
         if !despawn {
@@ -231,7 +225,7 @@ fn despawn_enemies(
         if despawn {
             explosions.send(SpawnExplosion {
                 position: transform.translation,
-                material: space_ship.material(),
+                appearance: *appearance,
                 kind: ExplosionKind::Medium,
             });
             commands.entity(entity).despawn();
@@ -1,6 +1,10 @@
-use super::{GravityMultiplier, Velocity};
+use super::{
+    presentation::material, Appearance, ApplyVelocity, GravityMultiplier, Interpolated, Velocity,
+};
 use crate::{
     assets::{AudioAssets, GameAssets},
+    mixer::{AudioChannel, ChannelAudioBundle},
+    settings::Settings,
     AppState,
 };
 use bevy::{
@@ -13,12 +17,17 @@ pub struct ExplosionPlugin;
 
 impl Plugin for ExplosionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnExplosion>();
-
         // Update
         app.add_systems(
             Update,
-            (spawn_explosions, update_explosions, update_particles)
+            (spawn_explosions, update_explosions).run_if(in_state(AppState::Game)),
+        );
+
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            update_particles
+                .after(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
         );
     }
@@ -27,7 +36,7 @@ impl Plugin for ExplosionPlugin {
 #[derive(Debug, Event)]
 pub struct SpawnExplosion {
     pub position: Vec3,
-    pub material: Handle<ColorMaterial>,
+    pub appearance: Appearance,
     pub kind: ExplosionKind,
 }
 
@@ -109,6 +118,7 @@ struct Explosion {
     timer: Timer,
 }
 
+/// Turns to face where it flies, as gravity bends its path.
 #[derive(Debug, Component)]
 struct Particle;
 
@@ -121,11 +131,19 @@ fn spawn_explosions(
     mut commands: Commands,
     audio_assets: Res<AudioAssets>,
     assets: Res<GameAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<Settings>,
 ) {
     // The volume of the explosion sound is reduced by 50% for each subsequent explosion in the same frame.
     let mut volume_multiplier = 1.0;
 
     for spawn in events.read() {
+        let particle_count = if settings.screen_effects {
+            spawn.kind.particle_count()
+        } else {
+            0
+        };
+        let particle_material = material(spawn.appearance, &assets, &mut materials);
         commands
             .spawn((
                 SpatialBundle::default(),
@@ -135,19 +153,20 @@ fn spawn_explosions(
                 StateScoped(AppState::Game),
             ))
             .with_children(|builder| {
-                for i in 0..spawn.kind.particle_count() {
-                    let alpha =
-                        (i as f32 / spawn.kind.particle_count() as f32) * std::f32::consts::TAU;
+                for i in 0..particle_count {
+                    let alpha = (i as f32 / particle_count as f32) * std::f32::consts::TAU;
                     let velocity = Velocity(
                         Vec3::new(f32::cos(alpha), f32::sin(alpha), 0.0)
                             * spawn.kind.initial_speed(),
                     );
                     builder.spawn((
+                        Particle,
                         velocity,
                         GravityMultiplier(spawn.kind.gravity_multiplier()),
+                        Interpolated::default(),
                         MaterialMesh2dBundle {
                             mesh: assets.explosion_mesh.clone(),
-                            material: spawn.material.clone(),
+                            material: particle_material.clone(),
                             transform: Transform::from_translation(spawn.position)
                                 .with_scale(Vec3::splat(spawn.kind.size()))
                                 .with_rotation(rot_from_velocity(*velocity)),
@@ -157,15 +176,16 @@ fn spawn_explosions(
                     ));
                 }
             });
-        commands.spawn(AudioBundle {
-            source: spawn.kind.audio_source(&audio_assets),
-            settings: PlaybackSettings {
+        commands.spawn(ChannelAudioBundle::new(
+            AudioChannel::Sfx,
+            spawn.kind.audio_source(&audio_assets),
+            PlaybackSettings {
                 mode: PlaybackMode::Despawn,
                 volume: Volume::new(volume_multiplier * spawn.kind.audio_volume()),
                 speed: spawn.kind.audio_speed(),
                 ..default()
             },
-        });
+        ));
 
         volume_multiplier *= 0.5;
     }
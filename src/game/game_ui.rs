@@ -1,4 +1,8 @@
-use super::{Health, Home, Player};
+use super::{
+    stats::{format_time, RunStats},
+    versus::{VersusScore, ROUNDS_TO_WIN},
+    GameMode, Health, Home, Player,
+};
 use crate::{assets::GameAssets, AppState};
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 
@@ -13,7 +17,7 @@ impl Plugin for GameUiPlugin {
         // Update
         app.add_systems(
             Update,
-            (update, update_root).run_if(in_state(AppState::Game)),
+            (update, update_root, update_stats).run_if(in_state(AppState::Game)),
         );
     }
 }
@@ -25,51 +29,82 @@ struct Root;
 struct HealthBarHome(f32);
 
 #[derive(Debug, Component)]
-struct HealthBarPlayer(f32);
+struct HealthBarPlayer {
+    index: usize,
+    fraction: f32,
+}
+
+#[derive(Debug, Component)]
+struct StatsText;
 
 fn update(
     time: Res<Time>,
     mut health_bar_player: Query<(&mut Transform, &mut HealthBarPlayer), Without<HealthBarHome>>,
     mut health_bar_home: Query<(&mut Transform, &mut HealthBarHome), Without<HealthBarPlayer>>,
-    players: Query<&Health, With<Player>>,
+    players: Query<(&Player, &Health)>,
     homes: Query<&Health, With<Home>>,
 ) {
-    // Get health bars
-    let Ok((mut health_bar_player_transform, mut health_bar_player)) =
-        health_bar_player.get_single_mut()
-    else {
-        return;
-    };
-    let Ok((mut health_bar_home_transform, mut health_bar_home)) = health_bar_home.get_single_mut()
-    else {
-        return;
-    };
+    // Update health bars
+    if let Ok((mut health_bar_home_transform, mut health_bar_home)) =
+        health_bar_home.get_single_mut()
+    {
+        let home_health_fraction = match homes.get_single() {
+            Ok(home) => home.fraction(),
+            Err(_) => 0.0,
+        };
+
+        health_bar_home.0 = f32::lerp(
+            health_bar_home.0,
+            home_health_fraction,
+            1.0 - f32::exp(f32::ln(0.9) * 60.0 * time.delta_seconds()),
+        );
+        health_bar_home_transform.translation.x = 100.0 - 200.0 * health_bar_home.0 / 2.0;
+        health_bar_home_transform.scale.y = health_bar_home.0;
+    }
+
+    for (mut health_bar_player_transform, mut health_bar_player) in &mut health_bar_player {
+        let Some((_, player)) = players
+            .iter()
+            .find(|(player, _)| player.index == health_bar_player.index)
+        else {
+            continue;
+        };
 
-    // Get health
-    let Ok(player) = players.get_single() else {
+        health_bar_player.fraction = f32::lerp(
+            health_bar_player.fraction,
+            player.fraction(),
+            1.0 - f32::exp(f32::ln(0.9) * 60.0 * time.delta_seconds()),
+        );
+        health_bar_player_transform.translation.x =
+            100.0 - 200.0 * health_bar_player.fraction / 2.0;
+        health_bar_player_transform.scale.y = health_bar_player.fraction;
+    }
+}
+
+fn update_stats(
+    stats: Res<RunStats>,
+    versus_score: Option<Res<VersusScore>>,
+    mut text: Query<&mut Text, With<StatsText>>,
+) {
+    let Ok(mut text) = text.get_single_mut() else {
         return;
     };
-    let home_health_fraction = match homes.get_single() {
-        Ok(home) => home.fraction(),
-        Err(_) => 0.0,
-    };
 
-    // Update health bars
-    health_bar_player.0 = f32::lerp(
-        health_bar_player.0,
-        player.fraction(),
-        1.0 - f32::exp(f32::ln(0.9) * 60.0 * time.delta_seconds()),
-    );
-    health_bar_player_transform.translation.x = 100.0 - 200.0 * health_bar_player.0 / 2.0;
-    health_bar_player_transform.scale.y = health_bar_player.0;
+    if let Some(score) = versus_score {
+        text.sections[0].value = format!(
+            "Player 1: {}   Player 2: {}   First to {ROUNDS_TO_WIN} wins",
+            score.wins[0], score.wins[1],
+        );
+        return;
+    }
 
-    health_bar_home.0 = f32::lerp(
-        health_bar_home.0,
-        home_health_fraction,
-        1.0 - f32::exp(f32::ln(0.9) * 60.0 * time.delta_seconds()),
+    text.sections[0].value = format!(
+        "Score {}   Kills {}   Accuracy {:.0}%   Time {}",
+        stats.score(),
+        stats.kills,
+        stats.accuracy() * 100.0,
+        format_time(stats.time_survived),
     );
-    health_bar_home_transform.translation.x = 100.0 - 200.0 * health_bar_home.0 / 2.0;
-    health_bar_home_transform.scale.y = health_bar_home.0;
 }
 
 fn update_root(
@@ -87,7 +122,26 @@ fn update_root(
     };
 }
 
-fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+fn setup(mut commands: Commands, assets: Res<GameAssets>, mode: Res<GameMode>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 20.0,
+                color: Color::srgb(0.9, 0.9, 0.9),
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        }),
+        StatsText,
+        StateScoped(AppState::Game),
+    ));
+
     commands
         .spawn((
             SpatialBundle {
@@ -98,6 +152,11 @@ fn setup(mut commands: Commands, assets: Res<GameAssets>) {
             StateScoped(AppState::Game),
         ))
         .with_children(|builder| {
+            if !mode.defends_home() {
+                spawn_player_health_bars(builder, 480.0, &assets, *mode);
+                return;
+            }
+
             // Planet
             builder.spawn(MaterialMesh2dBundle {
                 mesh: assets.health_bar_mesh.clone(),
@@ -123,31 +182,46 @@ fn setup(mut commands: Commands, assets: Res<GameAssets>) {
                 ..default()
             });
 
-            // Player
-            builder.spawn(MaterialMesh2dBundle {
+            // Players
+            spawn_player_health_bars(builder, 445.0, &assets, *mode);
+        });
+}
+
+fn spawn_player_health_bars(
+    builder: &mut ChildBuilder,
+    top: f32,
+    assets: &GameAssets,
+    mode: GameMode,
+) {
+    for index in 0..mode.players() {
+        let y = top - 35.0 * index as f32;
+        builder.spawn(MaterialMesh2dBundle {
+            mesh: assets.health_bar_mesh.clone(),
+            material: assets.health_bar_material_gray.clone(),
+            transform: Transform::from_translation(Vec3::new(0.0, y, 0.0))
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+            ..default()
+        });
+        builder.spawn((
+            MaterialMesh2dBundle {
                 mesh: assets.health_bar_mesh.clone(),
-                material: assets.health_bar_material_gray.clone(),
-                transform: Transform::from_translation(Vec3::new(0.0, 445.0, 0.0))
+                material: assets.health_bar_material_green.clone(),
+                transform: Transform::from_translation(Vec3::new(0.0, y, 1.0))
                     .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
                 ..default()
-            });
-            builder.spawn((
-                MaterialMesh2dBundle {
-                    mesh: assets.health_bar_mesh.clone(),
-                    material: assets.health_bar_material_green.clone(),
-                    transform: Transform::from_translation(Vec3::new(0.0, 445.0, 1.0))
-                        .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
-                    ..default()
-                },
-                HealthBarPlayer(1.0),
-            ));
-            builder.spawn(MaterialMesh2dBundle {
-                mesh: assets.space_ship_mesh.clone(),
-                material: assets.player_space_ship_material.clone(),
-                transform: Transform::from_translation(Vec3::new(130.0, 445.0, 0.0)),
-                ..default()
-            });
+            },
+            HealthBarPlayer {
+                index,
+                fraction: 1.0,
+            },
+        ));
+        builder.spawn(MaterialMesh2dBundle {
+            mesh: assets.space_ship_mesh.clone(),
+            material: assets.player_space_ship_materials[index].clone(),
+            transform: Transform::from_translation(Vec3::new(130.0, y, 0.0)),
+            ..default()
         });
+    }
 }
 
 fn cleanup(mut _commands: Commands) {}
@@ -1,4 +1,4 @@
-use super::{ApplyVelocity, Velocity};
+use super::{ApplyVelocity, UpdateBullets, Velocity};
 use crate::AppState;
 use bevy::prelude::*;
 
@@ -6,16 +6,21 @@ pub struct GravityPlugin;
 
 impl Plugin for GravityPlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             apply_gravity
+                .in_set(ApplyGravity)
+                .after(UpdateBullets)
                 .before(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
         );
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct ApplyGravity;
+
 #[derive(Debug, Deref, DerefMut, Component)]
 pub struct Mass(pub f32);
 
@@ -1,6 +1,9 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 
-#[derive(Debug, Clone, Copy, Component)]
+/// Deserialized from its maximum, e.g. `home_health: 300.0`.
+#[derive(Debug, Clone, Copy, Component, Deserialize)]
+#[serde(from = "f32")]
 pub struct Health {
     max: f32,
     current: f32,
@@ -11,9 +14,9 @@ impl Health {
         Self { max, current: max }
     }
 
-    // pub fn max(&self) -> f32 {
-    //     self.max
-    // }
+    pub fn max(&self) -> f32 {
+        self.max
+    }
 
     pub fn current(&self) -> f32 {
         self.current
@@ -31,3 +34,9 @@ impl Health {
         self.current = 0.0;
     }
 }
+
+impl From<f32> for Health {
+    fn from(max: f32) -> Self {
+        Self::new(max)
+    }
+}
@@ -1,4 +1,6 @@
-use super::{ApplyVelocity, ExplosionKind, GameState, Health, SpawnExplosion};
+use super::{
+    Appearance, ApplyVelocity, ExplosionKind, Health, ReadInput, SpawnExplosion, UpdateBullets,
+};
 use crate::AppState;
 use bevy::prelude::*;
 
@@ -6,13 +8,15 @@ pub struct HomePlugin;
 
 impl Plugin for HomePlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
+            // Before bullets hit the home planet again, so the last hit still counts.
             explode
+                .after(ReadInput)
+                .before(UpdateBullets)
                 .before(ApplyVelocity)
-                .run_if(in_state(AppState::Game))
-                .run_if(in_state(GameState::Running)),
+                .run_if(in_state(AppState::Game)),
         );
     }
 }
@@ -23,9 +27,9 @@ pub struct Home;
 fn explode(
     mut commands: Commands,
     mut explosions: EventWriter<SpawnExplosion>,
-    mut homes: Query<(Entity, &Health, &Transform, &Handle<ColorMaterial>), With<Home>>,
+    homes: Query<(Entity, &Health, &Transform, &Appearance), With<Home>>,
 ) {
-    let Ok((entity, health, transform, material)) = homes.get_single_mut() else {
+    let Ok((entity, health, transform, appearance)) = homes.get_single() else {
         return;
     };
 
@@ -33,7 +37,7 @@ fn explode(
         commands.entity(entity).despawn();
         explosions.send(SpawnExplosion {
             position: transform.translation,
-            material: material.clone(),
+            appearance: *appearance,
             kind: ExplosionKind::Large,
         });
     }
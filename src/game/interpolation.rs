@@ -0,0 +1,92 @@
+use bevy::{prelude::*, transform::TransformSystem};
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedFirst, restore);
+        app.add_systems(FixedLast, record);
+        app.add_systems(
+            PostUpdate,
+            interpolate.before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Renders an entity simulated in [`FixedUpdate`] between its last two simulated states, so it
+/// moves smoothly at any frame rate.
+///
+/// The simulation only ever sees the simulated [`Transform`], which is restored before each step.
+#[derive(Debug, Default, Component)]
+pub struct Interpolated {
+    previous: Option<(Vec3, Quat)>,
+    current: Option<(Vec3, Quat)>,
+}
+
+impl Interpolated {
+    /// Skips the interpolation from the previous state, e.g. after a respawn.
+    pub fn snap(&mut self) {
+        self.current = None;
+    }
+
+    fn record(&mut self, transform: &Transform) {
+        let state = (transform.translation, transform.rotation);
+        self.previous = Some(self.current.unwrap_or(state));
+        self.current = Some(state);
+    }
+
+    fn lerp(&self, t: f32) -> Option<(Vec3, Quat)> {
+        let ((previous_translation, previous_rotation), (translation, rotation)) =
+            (self.previous?, self.current?);
+        Some((
+            previous_translation.lerp(translation, t),
+            previous_rotation.slerp(rotation, t),
+        ))
+    }
+}
+
+fn restore(mut objects: Query<(&Interpolated, &mut Transform)>) {
+    for (interpolated, mut transform) in &mut objects {
+        if let Some((translation, rotation)) = interpolated.current {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+fn record(mut objects: Query<(&mut Interpolated, &Transform)>) {
+    for (mut interpolated, transform) in &mut objects {
+        interpolated.record(transform);
+    }
+}
+
+fn interpolate(time: Res<Time<Fixed>>, mut objects: Query<(&Interpolated, &mut Transform)>) {
+    let t = time.overstep_fraction();
+    for (interpolated, mut transform) in &mut objects {
+        if let Some((translation, rotation)) = interpolated.lerp(t) {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_between_last_two_steps() {
+        let mut interpolated = Interpolated::default();
+        assert_eq!(interpolated.lerp(0.5), None);
+
+        interpolated.record(&Transform::from_xyz(0.0, 0.0, 0.0));
+        interpolated.record(&Transform::from_xyz(10.0, 0.0, 0.0));
+        let (translation, _) = interpolated.lerp(0.25).unwrap();
+        assert_eq!(translation, Vec3::new(2.5, 0.0, 0.0));
+
+        interpolated.snap();
+        interpolated.record(&Transform::from_xyz(100.0, 0.0, 0.0));
+        let (translation, _) = interpolated.lerp(0.25).unwrap();
+        assert_eq!(translation, Vec3::new(100.0, 0.0, 0.0));
+    }
+}
@@ -1,25 +1,46 @@
-use super::{enemy::Enemy, Bullet, GameState, Health, Home, Planet};
+use super::{
+    defends_home, enemy::Enemy, gravity::ApplyGravity, playing, stats::RecordStats, Bullet,
+    GameState, Health, Home, Planet, PLANET_RADIUS, STAR_RADIUS,
+};
 use crate::AppState;
-use bevy::prelude::*;
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+};
+use serde::Deserialize;
 
 pub struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
+        app.init_asset::<Level>().init_asset_loader::<LevelLoader>();
+
         // Setup and cleanup
         app.add_systems(OnEnter(AppState::Game), setup);
         app.add_systems(OnExit(AppState::Game), cleanup);
 
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             check_if_ended
+                .after(RecordStats)
+                .before(ApplyGravity)
                 .run_if(in_state(AppState::Game))
-                .run_if(in_state(GameState::Running)),
+                .run_if(playing)
+                .run_if(defends_home),
         );
+    }
+}
+
+/// Shows the home planet's laser destroying the remaining enemies when the game is won.
+pub struct HomeLaserPlugin;
+
+impl Plugin for HomeLaserPlugin {
+    fn build(&self, app: &mut App) {
+        // Update
         app.add_systems(
             Update,
             home_laser
-                .after(check_if_ended)
                 .run_if(in_state(AppState::Game))
                 .run_if(in_state(GameState::GameWon)),
         );
@@ -37,7 +58,6 @@ fn home_laser(
     time: Res<Time>,
     mut home_laser: ResMut<HomeLaser>,
     homes: Query<&Transform, With<Home>>,
-    mut enemies: Query<(&mut Health, &Transform), With<Enemy>>,
 ) {
     if home_laser.timer.tick(time.delta()).finished() {
         return;
@@ -50,11 +70,6 @@ fn home_laser(
     let factor = home_laser.timer.fraction_remaining().powf(0.1);
     let color = Color::srgb(5.0 * factor, 0.1 * factor, 0.1 * factor);
 
-    for (mut enemy_health, enemy_transform) in &mut enemies {
-        enemy_health.set_dead();
-        home_laser.enemies.push(*enemy_transform);
-    }
-
     for enemy_transform in &home_laser.enemies {
         gizmos.line_2d(
             home_transform.translation.xy(),
@@ -65,7 +80,6 @@ fn home_laser(
 }
 
 fn check_if_ended(
-    mut commands: Commands,
     mut home_laser: ResMut<HomeLaser>,
     homes: Query<(&Planet, &Health), (With<Home>, Without<Enemy>)>,
     mut enemies: Query<(&mut Health, &Transform), With<Enemy>>,
@@ -86,59 +100,19 @@ fn check_if_ended(
     };
 
     match state {
-        GameState::GameOver => {
-            next_state_game.set(GameState::GameOver);
-            spawn_text(
-                &mut commands,
-                "YOU LOSE!".to_string(),
-                Color::srgb(8.0, 0.6, 0.6),
-            );
-        }
+        GameState::GameOver => next_state_game.set(GameState::GameOver),
         GameState::GameWon => {
             next_state_game.set(GameState::GameWon);
-            spawn_text(
-                &mut commands,
-                "YOU WIN!".to_string(),
-                Color::srgb(0.9, 8.0, 0.9),
-            );
 
             for (mut enemy_health, enemy_transform) in &mut enemies {
                 enemy_health.set_dead();
                 home_laser.enemies.push(*enemy_transform);
             }
         }
-        GameState::Running => (),
+        GameState::Running | GameState::Paused => (),
     }
 }
 
-fn spawn_text(commands: &mut Commands, text: String, color: Color) {
-    commands
-        .spawn((
-            NodeBundle {
-                style: Style {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(70.0),
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::Center,
-                    flex_direction: FlexDirection::Column,
-                    ..default()
-                },
-                ..default()
-            },
-            StateScoped(AppState::Game),
-        ))
-        .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                text,
-                TextStyle {
-                    font_size: 80.0,
-                    color,
-                    ..default()
-                },
-            ));
-        });
-}
-
 fn setup(mut commands: Commands) {
     commands.insert_resource(HomeLaser {
         timer: Timer::from_seconds(1.0, TimerMode::Once),
@@ -147,16 +121,16 @@ fn setup(mut commands: Commands) {
 }
 
 fn cleanup(mut commands: Commands) {
-    commands.remove_resource::<Level>();
+    // The level itself is kept, so the game can be restarted.
     commands.remove_resource::<HomeLaser>();
 }
 
-#[derive(Debug, Resource)]
+/// A level as described by a `*.level.ron` file in `assets/levels`.
+#[derive(Debug, Clone, Asset, TypePath, Resource, Deserialize)]
 pub struct Level {
-    #[allow(dead_code)]
     pub name: String,
-
-    pub home_orbit_time: f32,
+    /// Used to order the levels in the main menu.
+    pub difficulty: u32,
 
     pub home_health: Health,
     pub player_health: Health,
@@ -167,60 +141,215 @@ pub struct Level {
 
     pub enemy_spawn_interval: f32,
     pub enemy_force_to_home_probability: f32,
+
+    pub star: StarDefinition,
+    pub planets: Vec<PlanetDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StarDefinition {
+    pub mass: f32,
+    pub color: (f32, f32, f32),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlanetDefinition {
+    pub orbit_radius: f32,
+    pub orbit_time: f32,
+    #[serde(default)]
+    pub orbit_progress: f32,
+    pub mass: f32,
+    /// Ignored for the home planet, which always uses the home planet material.
+    #[serde(default)]
+    pub color: Option<(f32, f32, f32)>,
+    #[serde(default)]
+    pub home: bool,
 }
 
 impl Level {
-    pub fn easy() -> Self {
-        Self {
-            name: "Easy".to_string(),
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_bytes(bytes)
+    }
 
-            home_orbit_time: 30.0,
+    /// Whether the level has this name (ignoring case) or difficulty, e.g. to pick it on the
+    /// command line.
+    pub fn matches(&self, name_or_difficulty: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name_or_difficulty)
+            || name_or_difficulty.parse() == Ok(self.difficulty)
+    }
 
-            home_health: Health::new(300.0),
-            player_health: Health::new(50.0),
-            enemy_health: Health::new(10.0),
+    /// Checks everything the game relies on when setting up the level and collects all problems.
+    pub fn validate(&self) -> Result<(), Vec<LevelError>> {
+        let mut errors = Vec::new();
+        let mut check = |ok: bool, path: String, message: &str| {
+            if !ok {
+                errors.push(LevelError {
+                    path,
+                    message: message.to_string(),
+                });
+            }
+        };
 
-            player_damage: 10.0,
-            enemy_damage: 10.0,
+        check(!self.name.is_empty(), "name".into(), "must not be empty");
 
-            enemy_spawn_interval: 5.0,
-            enemy_force_to_home_probability: 0.1,
+        for (path, health) in [
+            ("home_health", self.home_health),
+            ("player_health", self.player_health),
+            ("enemy_health", self.enemy_health),
+        ] {
+            check(health.max() > 0.0, path.into(), "must be positive");
+        }
+        for (path, damage) in [
+            ("player_damage", self.player_damage),
+            ("enemy_damage", self.enemy_damage),
+        ] {
+            check(damage >= 0.0, path.into(), "must not be negative");
         }
-    }
 
-    pub fn medium() -> Self {
-        Self {
-            name: "Medium".to_string(),
+        check(
+            self.enemy_spawn_interval > 0.0,
+            "enemy_spawn_interval".into(),
+            "must be positive",
+        );
+        check(
+            (0.0..=1.0).contains(&self.enemy_force_to_home_probability),
+            "enemy_force_to_home_probability".into(),
+            "must be between 0 and 1",
+        );
 
-            home_orbit_time: 60.0,
+        check(
+            self.star.mass >= 0.0,
+            "star.mass".into(),
+            "must not be negative",
+        );
 
-            home_health: Health::new(400.0),
-            player_health: Health::new(30.0),
-            enemy_health: Health::new(20.0),
+        for (i, planet) in self.planets.iter().enumerate() {
+            check(
+                planet.orbit_time > 0.0,
+                format!("planets[{i}].orbit_time"),
+                "must be positive",
+            );
+            check(
+                planet.orbit_progress.is_finite(),
+                format!("planets[{i}].orbit_progress"),
+                "must be finite",
+            );
+            check(
+                planet.mass >= 0.0,
+                format!("planets[{i}].mass"),
+                "must not be negative",
+            );
+            check(
+                planet.orbit_radius > STAR_RADIUS + PLANET_RADIUS,
+                format!("planets[{i}].orbit_radius"),
+                "orbit must not touch the star",
+            );
+            for (j, other) in self.planets.iter().enumerate().take(i) {
+                check(
+                    (planet.orbit_radius - other.orbit_radius).abs() >= 2.0 * PLANET_RADIUS,
+                    format!("planets[{i}].orbit_radius"),
+                    &format!("orbit overlaps the orbit of planets[{j}]"),
+                );
+            }
+        }
 
-            player_damage: 10.0,
-            enemy_damage: 10.0,
+        match self.planets.iter().filter(|planet| planet.home).count() {
+            0 => check(false, "planets".into(), "missing home planet"),
+            1 => (),
+            _ => check(false, "planets".into(), "more than one home planet"),
+        }
 
-            enemy_spawn_interval: 5.0,
-            enemy_force_to_home_probability: 0.15,
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
         }
     }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelError {
+    /// Path of the offending field, e.g. `planets[1].orbit_time`.
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for LevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
 
-    pub fn hard() -> Self {
-        Self {
-            name: "Hard".to_string(),
+#[derive(Default)]
+struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = Level;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Level, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(Level::from_ron(&bytes)?)
+    }
 
-            home_orbit_time: 90.0,
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
 
-            home_health: Health::new(500.0),
-            player_health: Health::new(20.0),
-            enemy_health: Health::new(20.0),
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            player_damage: 10.0,
-            enemy_damage: 10.0,
+    #[test]
+    fn shipped_levels_are_valid() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels");
+        let mut count = 0;
 
-            enemy_spawn_interval: 4.0,
-            enemy_force_to_home_probability: 0.2,
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let bytes = std::fs::read(&path).unwrap();
+            let level =
+                Level::from_ron(&bytes).unwrap_or_else(|err| panic!("{}: {err}", path.display()));
+
+            if let Err(errors) = level.validate() {
+                panic!("{}: {errors:#?}", path.display());
+            }
+            count += 1;
         }
+
+        assert!(count > 0);
+    }
+
+    #[test]
+    fn invalid_level_reports_field_paths() {
+        let mut level =
+            Level::from_ron(include_bytes!("../../assets/levels/easy.level.ron")).unwrap();
+        level.enemy_spawn_interval = 0.0;
+        level.planets[1].orbit_time = -1.0;
+        level.planets[2].orbit_radius = level.planets[1].orbit_radius + 1.0;
+        level.planets[1].home = false;
+
+        let paths = level
+            .validate()
+            .unwrap_err()
+            .into_iter()
+            .map(|err| err.path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            [
+                "enemy_spawn_interval",
+                "planets[1].orbit_time",
+                "planets[2].orbit_radius",
+                "planets",
+            ]
+        );
     }
 }
@@ -1,50 +1,117 @@
 mod background;
+mod bot;
 mod bullet;
+mod end_screen;
 mod enemy;
 mod explosion;
 mod game_ui;
 mod gravity;
 mod health;
 mod home;
+mod interpolation;
 mod level;
+mod pause;
 mod planet;
 mod player;
+mod presentation;
 mod quit;
+mod replay;
+mod rng;
 mod show_home_progress;
 mod space_ship;
 mod star;
+mod stats;
 mod velocity;
+mod versus;
 
 use self::{
     bullet::*,
-    explosion::*,
-    gravity::*,
-    health::*, // enemy::*,
-    home::*,
+    gravity::*, // enemy::*,
+    interpolation::*,
     planet::*,
     player::*,
+    rng::*,
     space_ship::*,
     star::*,
     velocity::*,
 };
 use crate::{
-    assets::{AudioAssets, GameAssets},
+    assets::AudioAssets,
     camera::GameCameraBundle,
+    error_screen::{ErrorAction, ErrorScreen},
+    mixer::{AudioChannel, ChannelAudioBundle},
     AppState,
 };
 use bevy::{audio::PlaybackMode, prelude::*};
+use serde::{Deserialize, Serialize};
 
-pub use self::level::Level;
+pub use self::{
+    bot::Bot,
+    bullet::{Bullet, BulletHit},
+    enemy::Enemy,
+    explosion::{ExplosionKind, SpawnExplosion},
+    gravity::Mass,
+    health::Health,
+    home::Home,
+    level::{Level, LevelError, PlanetDefinition, StarDefinition},
+    planet::Planet,
+    player::{Player, PlayerDied, PlayerInput},
+    presentation::PresentationPlugin,
+    replay::{Replay, ReplayInput, ReplayPlayback, ReplayRecorder, Replays, StoredReplay},
+    rng::{NextSeed, Seed},
+    space_ship::{ShotFired, SpaceShip},
+    star::Star,
+    stats::RunStats,
+    velocity::Velocity,
+    versus::VersusScore,
+};
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        // Setup and cleanup
+        // Setup
         app.add_systems(OnEnter(AppState::Game), setup);
+
+        app.add_plugins((
+            SimulationPlugin,
+            level::HomeLaserPlugin,
+            PresentationPlugin,
+            explosion::ExplosionPlugin,
+            game_ui::GameUiPlugin,
+            background::BackgroundPlugin,
+            quit::QuitPlugin,
+            show_home_progress::ShowHomeProgressPlugin,
+            pause::PausePlugin,
+            end_screen::EndScreenPlugin,
+            interpolation::InterpolationPlugin,
+            replay::ReplayControlsPlugin,
+        ));
+    }
+}
+
+/// The game without presentation or input devices, e.g. to run headless. Player input is read
+/// from the input devices only if the `ActionsPlugin` is added, otherwise it is set directly.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(OnEnter(AppState::Game), spawn_world);
         app.add_systems(OnExit(AppState::Game), cleanup);
 
-        app.init_state::<GameState>();
+        app.init_state::<GameState>()
+            .enable_state_scoped_entities::<GameState>();
+        app.init_resource::<GameMode>();
+
+        // Sent by the simulation, shown by the `ExplosionPlugin`.
+        app.add_event::<SpawnExplosion>();
+
+        // Start
+        app.add_systems(OnEnter(AppState::StartGame), start_game);
+
+        // The simulation runs in `FixedUpdate`, independent of the frame rate.
+        app.insert_resource(Time::<Fixed>::from_hz(SIMULATION_RATE));
 
         app.add_plugins((
             level::LevelPlugin,
@@ -57,105 +124,250 @@ impl Plugin for GamePlugin {
             space_ship::SpaceShipPlugin,
             player::PlayerPlugin,
             enemy::EnemyPlugin,
-            explosion::ExplosionPlugin,
-            game_ui::GameUiPlugin,
-            background::BackgroundPlugin,
-            quit::QuitPlugin,
-            show_home_progress::ShowHomeProgressPlugin,
+            stats::StatsPlugin,
+            versus::VersusPlugin,
+            rng::RngPlugin,
+            replay::ReplayPlugin,
+            bot::BotPlugin,
         ));
     }
 }
 
+/// Simulation steps per second.
+pub const SIMULATION_RATE: f64 = 60.0;
+
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum GameState {
     #[default]
     Running,
+    Paused,
     GameWon,
     GameOver,
 }
 
+/// Selected in the main menu.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Resource, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Single,
+    /// Two players defend the home planet together.
+    Coop,
+    /// Two players fight each other, without enemies or a home planet.
+    Versus,
+}
+
+impl GameMode {
+    pub fn players(self) -> usize {
+        match self {
+            GameMode::Single => 1,
+            GameMode::Coop | GameMode::Versus => 2,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            GameMode::Single => GameMode::Coop,
+            GameMode::Coop => GameMode::Versus,
+            GameMode::Versus => GameMode::Single,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Single => "Single Player",
+            GameMode::Coop => "Co-op",
+            GameMode::Versus => "Versus",
+        }
+    }
+
+    pub fn defends_home(self) -> bool {
+        self != GameMode::Versus
+    }
+
+    /// The high scores of each mode are kept separately.
+    pub fn high_score_table(self, level: &str) -> String {
+        match self {
+            GameMode::Single => level.to_string(),
+            GameMode::Coop => format!("{level} (Co-op)"),
+            GameMode::Versus => format!("{level} (Versus)"),
+        }
+    }
+}
+
+/// Run condition for the systems of the home planet and the enemies attacking it.
+pub fn defends_home(mode: Res<GameMode>) -> bool {
+    mode.defends_home()
+}
+
+/// Run condition for the simulation until the game is won or lost.
+///
+/// States only change between frames, while the number of simulation steps per frame varies. So
+/// the game counts as over as soon as that is decided, and paused until virtual time is paused,
+/// to play out the same in a replay.
+pub fn playing(
+    game_state: Res<State<GameState>>,
+    next_state_game: Res<NextState<GameState>>,
+) -> bool {
+    let state = match *next_state_game {
+        NextState::Pending(state) => state,
+        NextState::Unchanged => **game_state,
+    };
+    matches!(state, GameState::Running | GameState::Paused)
+}
+
 #[derive(Debug, Component)]
 pub struct Collider {
     pub radius: f32,
     pub group: u32,
 }
 
-fn setup(
-    mut commands: Commands,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    level: Res<Level>,
-    audio_assets: Res<AudioAssets>,
-    assets: Res<GameAssets>,
-) {
-    commands.spawn((GameCameraBundle::default(), StateScoped(AppState::Game)));
+/// What an object looks like. Set by the simulation, and shown by the `PresentationPlugin`.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub enum Appearance {
+    /// The index of the player.
+    Player(usize),
+    PlayerBullet(usize),
+    Enemy,
+    EnemyBullet,
+    Home,
+    Planet(Color),
+    Star(Color),
+}
 
-    commands.spawn((
-        PlayerBundle::new(
-            Vec3::new(-300.0, 200.0, 0.0),
-            0.0,
-            &level,
-            &audio_assets,
-            &assets,
-        ),
-        StateScoped(AppState::Game),
+fn setup(mut commands: Commands, audio_assets: Res<AudioAssets>) {
+    commands.spawn((GameCameraBundle::default(), StateScoped(AppState::Game)));
+    commands.spawn(ChannelAudioBundle::new(
+        AudioChannel::Sfx,
+        audio_assets.force_field_000.clone(),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            ..default()
+        },
     ));
+}
+
+fn spawn_world(mut commands: Commands, level: Res<Level>, mode: Res<GameMode>) {
+    for (index, (position, rotation)) in PLAYER_STARTS.into_iter().take(mode.players()).enumerate()
+    {
+        // In versus, the players are in separate collider groups to hit each other.
+        let collider_group = match (*mode, index) {
+            (GameMode::Versus, 1) => 0b1000,
+            _ => 0b1,
+        };
+        commands.spawn((
+            PlayerBundle::new(index, collider_group, position, rotation, &level),
+            StateScoped(AppState::Game),
+        ));
+    }
 
     // Star
+    let (r, g, b) = level.star.color;
     commands.spawn((
-        StarBundle::new(
-            Mass(200_000.0),
-            Color::srgb(4.0, 4.0, 0.8),
-            &assets,
-            &mut materials,
-        ),
+        StarBundle::new(Mass(level.star.mass), Color::srgb(r, g, b)),
         StateScoped(AppState::Game),
     ));
 
-    commands.spawn((
-        PlanetBundle::new(
-            150.0,
-            level.home_orbit_time / 2.0,
-            0.5,
-            Mass(100_000.0),
-            materials.add(Color::srgb(2.0, 1.5, 0.2)),
-            &assets,
-        ),
-        StateScoped(AppState::Game),
-    ));
+    // Planets
+    for planet in &level.planets {
+        if planet.home && !mode.defends_home() {
+            continue;
+        }
 
-    commands.spawn((
-        PlanetBundle::new(
-            300.0,
-            level.home_orbit_time,
-            0.0,
-            Mass(100_000.0),
-            assets.home_planet_material.clone(),
-            &assets,
-        ),
-        Home,
-        level.home_health,
-        StateScoped(AppState::Game),
-    ));
+        let appearance = match (planet.home, planet.color) {
+            (true, _) => Appearance::Home,
+            (false, Some((r, g, b))) => Appearance::Planet(Color::srgb(r, g, b)),
+            (false, None) => Appearance::Planet(Color::WHITE),
+        };
 
-    commands.spawn((
-        PlanetBundle::new(
-            450.0,
-            level.home_orbit_time * 2.0,
-            0.8,
-            Mass(100_000.0),
-            materials.add(Color::srgb(1.8, 0.4, 0.9)),
-            &assets,
-        ),
-        StateScoped(AppState::Game),
-    ));
+        let mut entity = commands.spawn((
+            PlanetBundle::new(
+                planet.orbit_radius,
+                planet.orbit_time,
+                planet.orbit_progress,
+                Mass(planet.mass),
+                appearance,
+            ),
+            StateScoped(AppState::Game),
+        ));
+        if planet.home {
+            entity.insert((Home, level.home_health));
+        }
+    }
+}
 
-    commands.spawn(AudioBundle {
-        source: audio_assets.force_field_000.clone(),
-        settings: PlaybackSettings {
-            mode: PlaybackMode::Despawn,
-            ..default()
-        },
-    });
+fn cleanup(mut next_state_game: ResMut<NextState<GameState>>) {
+    // Leaving the game while paused must not keep the game (and virtual time) paused.
+    next_state_game.set(GameState::Running);
 }
 
-fn cleanup(mut _commands: Commands) {}
+fn start_game(
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    next_state.set(AppState::Game);
+    next_state_game.set(GameState::Running);
+}
+
+/// Starts (or restarts) the game with the given level, or shows why the level is invalid. Returns
+/// whether the game starts.
+pub fn start_level(
+    commands: &mut Commands,
+    level: &Level,
+    next_state: &mut NextState<AppState>,
+) -> bool {
+    if let Err(errors) = level.validate() {
+        commands.insert_resource(ErrorScreen {
+            title: format!("Level \"{}\" is invalid", level.name),
+            details: errors.iter().map(ToString::to_string).collect(),
+            actions: vec![ErrorAction::MainMenu],
+        });
+        next_state.set(AppState::Error);
+        return false;
+    }
+
+    commands.insert_resource(level.clone());
+    commands.remove_resource::<ReplayPlayback>();
+    next_state.set(AppState::StartGame);
+    true
+}
+
+/// Starts watching the replay, which has to be of the given level.
+pub fn start_replay(
+    commands: &mut Commands,
+    level: &Level,
+    replay: Replay,
+    next_state: &mut NextState<AppState>,
+) {
+    if start_level(commands, level, next_state) {
+        commands.insert_resource(replay.mode);
+        commands.insert_resource(ReplayPlayback::new(replay));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_level_does_not_start_replay() {
+        let mut level =
+            Level::from_ron(include_bytes!("../../assets/levels/easy.level.ron")).unwrap();
+        level.enemy_spawn_interval = 0.0;
+        let replay = Replay {
+            level: level.name.clone(),
+            mode: GameMode::Coop,
+            seed: 1,
+            steps: 0,
+            inputs: Vec::new(),
+        };
+
+        let mut world = World::new();
+        let mut next_state = NextState::default();
+        start_replay(&mut world.commands(), &level, replay, &mut next_state);
+        world.flush();
+
+        assert!(matches!(next_state, NextState::Pending(AppState::Error)));
+        assert!(!world.contains_resource::<ReplayPlayback>());
+        assert!(!world.contains_resource::<GameMode>());
+    }
+}
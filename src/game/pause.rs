@@ -0,0 +1,155 @@
+use super::GameState;
+use crate::{
+    actions::{Action, ActionState},
+    options::OptionsState,
+    ui, AppState,
+};
+use bevy::{input::gamepad::GamepadConnectionEvent, prelude::*, window::WindowFocused};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(OnEnter(GameState::Paused), setup);
+        app.add_systems(OnExit(GameState::Paused), cleanup);
+
+        // Update
+        app.add_systems(
+            Update,
+            (
+                toggle_pause,
+                pause_on_focus_lost,
+                pause_on_gamepad_disconnected,
+            )
+                .run_if(in_state(AppState::Game)),
+        );
+        app.add_systems(
+            Update,
+            update
+                .run_if(in_state(AppState::Game))
+                .run_if(in_state(GameState::Paused)),
+        );
+    }
+}
+
+#[derive(Debug, Component)]
+enum ButtonAction {
+    Resume,
+    Restart,
+    Options,
+    QuitToMenu,
+}
+
+fn toggle_pause(
+    actions: Res<ActionState>,
+    game_state: Res<State<GameState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    if actions.just_pressed(Action::Pause) {
+        match **game_state {
+            GameState::Running => next_state_game.set(GameState::Paused),
+            GameState::Paused => next_state_game.set(GameState::Running),
+            GameState::GameWon | GameState::GameOver => (),
+        }
+    }
+}
+
+fn pause_on_focus_lost(
+    mut events: EventReader<WindowFocused>,
+    game_state: Res<State<GameState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    for event in events.read() {
+        if !event.focused && **game_state == GameState::Running {
+            next_state_game.set(GameState::Paused);
+        }
+    }
+}
+
+/// Unplugging a controller mid-game should not leave the ship uncontrolled.
+fn pause_on_gamepad_disconnected(
+    mut events: EventReader<GamepadConnectionEvent>,
+    game_state: Res<State<GameState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    for event in events.read() {
+        if event.disconnected() && **game_state == GameState::Running {
+            next_state_game.set(GameState::Paused);
+        }
+    }
+}
+
+fn update(
+    interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+    mut next_state_options: ResMut<NextState<OptionsState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                ButtonAction::Resume => next_state_game.set(GameState::Running),
+                ButtonAction::Restart => next_state.set(AppState::StartGame),
+                ButtonAction::Options => next_state_options.set(OptionsState::Open),
+                ButtonAction::QuitToMenu => next_state.set(AppState::MainMenu),
+            }
+        }
+    }
+}
+
+fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>, sinks: Query<&AudioSink>) {
+    time.pause();
+    for sink in &sinks {
+        sink.pause();
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                ..default()
+            },
+            StateScoped(GameState::Paused),
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "PAUSED",
+                    TextStyle {
+                        font_size: 80.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+            ui::spawn_button_with(parent, "Resume", ButtonAction::Resume);
+            ui::spawn_button_with(parent, "Restart", ButtonAction::Restart);
+            ui::spawn_button_with(parent, "Options", ButtonAction::Options);
+            ui::spawn_button_with(parent, "Quit to Menu", ButtonAction::QuitToMenu);
+        });
+}
+
+fn cleanup(
+    mut time: ResMut<Time<Virtual>>,
+    sinks: Query<&AudioSink>,
+    mut next_state_options: ResMut<NextState<OptionsState>>,
+) {
+    next_state_options.set(OptionsState::Closed);
+    time.unpause();
+    for sink in &sinks {
+        sink.play();
+    }
+}
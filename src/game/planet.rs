@@ -1,16 +1,25 @@
-use super::{gravity::Mass, Collider};
-use crate::{assets::GameAssets, AppState};
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use super::{gravity::Mass, Appearance, ApplyVelocity, Collider, Interpolated, ReadInput};
+use crate::AppState;
+use bevy::prelude::*;
 
 pub struct PlanetPlugin;
 
 impl Plugin for PlanetPlugin {
     fn build(&self, app: &mut App) {
-        // Update
-        app.add_systems(Update, update.run_if(in_state(AppState::Game)));
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            // First, so the rest of the step sees where the planets are now.
+            update
+                .before(ReadInput)
+                .before(ApplyVelocity)
+                .run_if(in_state(AppState::Game)),
+        );
     }
 }
 
+pub const PLANET_RADIUS: f32 = 8.0;
+
 #[derive(Debug, Component)]
 pub struct Planet {
     pub orbit_radius: f32,
@@ -31,7 +40,9 @@ pub struct PlanetBundle {
     pub planet: Planet,
     pub collider: Collider,
     pub mass: Mass,
-    pub mesh: MaterialMesh2dBundle<ColorMaterial>,
+    pub transform: TransformBundle,
+    pub interpolated: Interpolated,
+    pub appearance: Appearance,
 }
 
 impl PlanetBundle {
@@ -40,9 +51,7 @@ impl PlanetBundle {
         orbit_time: f32,
         orbit_progress: f32,
         mass: Mass,
-        material: Handle<ColorMaterial>,
-
-        assets: &GameAssets,
+        appearance: Appearance,
     ) -> Self {
         let planet = Planet {
             orbit_radius,
@@ -52,17 +61,16 @@ impl PlanetBundle {
 
         Self {
             collider: Collider {
-                radius: 8.0,
+                radius: PLANET_RADIUS,
                 group: 0b100,
             },
             mass,
-            mesh: MaterialMesh2dBundle {
-                mesh: assets.planet_mesh.clone(),
-                material,
-                transform: Transform::from_translation(planet.position()),
-                ..default()
-            },
+            transform: TransformBundle::from_transform(Transform::from_translation(
+                planet.position(),
+            )),
             planet,
+            interpolated: Interpolated::default(),
+            appearance,
         }
     }
 }
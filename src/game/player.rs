@@ -1,22 +1,37 @@
 use super::{
-    ApplyVelocity, Collider, ExplosionKind, GameState, Health, Level, Planet, SpaceShip,
-    SpaceShipBundle, SpawnExplosion, Star, Steering, UpdateSpaceShip, Velocity,
+    Appearance, ApplyVelocity, Bot, Collider, ExplosionKind, GameMode, GameRng, GameState, Health,
+    Interpolated, Level, Planet, ReplayPlayback, SpaceShip, SpaceShipBundle, SpawnExplosion, Star,
+    UpdateSpaceShip, UseGameRng, Velocity,
 };
 use crate::{
-    assets::{AudioAssets, GameAssets},
+    actions::{Action, ActionState, PlayerActions},
     AppState,
 };
 use bevy::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        app.add_event::<PlayerDied>();
+
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            read_input
+                .in_set(ReadInput)
+                .run_if(in_state(AppState::Game))
+                .run_if(resource_exists::<ActionState>)
+                .run_if(not(resource_exists::<ReplayPlayback>)),
+        );
         app.add_systems(
-            Update,
-            (update, dead)
+            FixedUpdate,
+            (
+                update.after(ReadInput).before(KillPlayers),
+                dead.in_set(KillPlayers).in_set(UseGameRng::RespawnPlayers),
+            )
                 .before(UpdateSpaceShip)
                 .before(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
@@ -24,87 +39,157 @@ impl Plugin for PlayerPlugin {
     }
 }
 
+/// Sets the [`PlayerInput`] of each player, either from the input devices or from a replay.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemSet)]
+pub struct ReadInput;
+
+/// Respawns dead players and sends [`PlayerDied`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct KillPlayers;
+
 #[derive(Debug, Component)]
-pub struct Player;
+pub struct Player {
+    /// 0 for the first player, 1 for the second player in co-op and versus.
+    pub index: usize,
+    /// Whether fire was held in the previous step, so every press shoots once, no matter how
+    /// many simulation steps run per frame.
+    fire_held: bool,
+}
+
+/// The start position and rotation of each player. The second player starts on the opposite
+/// side of the star.
+pub const PLAYER_STARTS: [(Vec3, f32); 2] = [
+    (Vec3::new(-300.0, 200.0, 0.0), 0.0),
+    (Vec3::new(300.0, -200.0, 0.0), std::f32::consts::PI),
+];
+
+/// The controls of a player in the current simulation step. Everything a player does goes
+/// through this, so it is all a replay has to record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Component, Serialize, Deserialize)]
+pub struct PlayerInput {
+    /// From -1 (full left) to 1 (full right).
+    pub steer: f32,
+    /// From 0 to 1.
+    pub throttle: f32,
+    pub brake: bool,
+    pub fire: bool,
+}
+
+#[derive(Debug, Event)]
+pub struct PlayerDied {
+    pub player: Entity,
+}
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
     pub player: Player,
+    pub input: PlayerInput,
     pub health: Health,
     pub space_ship: SpaceShipBundle,
 }
 
 impl PlayerBundle {
     pub fn new(
+        index: usize,
+        collider_group: u32,
         position: Vec3,
         rotation: f32,
         level: &Level,
-        audio_assets: &AudioAssets,
-        assets: &GameAssets,
     ) -> Self {
         Self {
-            player: Player,
+            player: Player {
+                index,
+                fire_held: false,
+            },
+            input: PlayerInput::default(),
             health: level.player_health,
             space_ship: SpaceShipBundle::new(
-                0b1,
+                collider_group,
                 Velocity(Vec3::ZERO),
                 position,
                 rotation,
-                assets.player_space_ship_material.clone(),
-                assets.player_bullet_material.clone(),
-                audio_assets,
-                assets,
+                Appearance::Player(index),
+                Appearance::PlayerBullet(index),
             ),
         }
     }
 }
 
+fn read_input(
+    mut players: Query<(&Player, &SpaceShip, &Transform, &mut PlayerInput), Without<Bot>>,
+    actions: Res<ActionState>,
+    mode: Res<GameMode>,
+) {
+    for (player, space_ship, transform, mut input) in &mut players {
+        // A single player can use all input devices.
+        let actions: &PlayerActions = match mode.players() {
+            1 => &actions,
+            _ => actions.player(player.index),
+        };
+        *input = PlayerInput {
+            steer: match actions.aim() {
+                Some(aim) => steer_toward(space_ship, transform, aim),
+                None => actions.steer(),
+            },
+            throttle: actions.throttle(),
+            brake: actions.pressed(Action::Brake),
+            fire: actions.pressed(Action::Fire),
+        };
+    }
+}
+
 fn update(
-    mut players: Query<(&mut SpaceShip, &mut Transform), With<Player>>,
-    input: Res<ButtonInput<KeyCode>>,
+    mut players: Query<(&mut Player, &PlayerInput, &mut SpaceShip, &mut Transform)>,
     time: Res<Time>,
     game_state: Res<State<GameState>>,
     level: Res<Level>,
 ) {
-    let Ok((mut space_ship, mut transform)) = players.get_single_mut() else {
-        return;
-    };
-
-    transform.scale = Vec3::lerp(
-        transform.scale,
-        Vec3::ONE,
-        1.0 - f32::exp(f32::ln(0.95) * 60.0 * time.delta_seconds()),
-    );
-
-    match **game_state {
-        GameState::Running | GameState::GameWon => {
-            space_ship.steering = match (
-                input.pressed(KeyCode::KeyA) || input.pressed(KeyCode::ArrowLeft),
-                input.pressed(KeyCode::KeyD) || input.pressed(KeyCode::ArrowRight),
-            ) {
-                (true, false) => Steering::Left,
-                (false, true) => Steering::Right,
-                _ => Steering::None,
-            };
-            space_ship.throttle = input.pressed(KeyCode::KeyW) || input.pressed(KeyCode::ArrowUp);
-            space_ship.brake = input.pressed(KeyCode::KeyS) || input.pressed(KeyCode::ArrowDown);
-            space_ship.shoot = input
-                .just_pressed(KeyCode::Space)
-                .then_some(level.player_damage);
+    for (mut player, input, mut space_ship, mut transform) in &mut players {
+        transform.scale = Vec3::lerp(
+            transform.scale,
+            Vec3::ONE,
+            1.0 - f32::exp(f32::ln(0.95) * 60.0 * time.delta_seconds()),
+        );
+
+        match **game_state {
+            GameState::GameOver => space_ship.stop(),
+            // Paused only until virtual time is paused, see `playing`.
+            GameState::Running | GameState::Paused | GameState::GameWon => {
+                space_ship.steer = input.steer;
+                space_ship.throttle = input.throttle;
+                space_ship.brake = input.brake;
+                space_ship.shoot = (input.fire && !player.fire_held).then_some(level.player_damage);
+                player.fire_held = input.fire;
+            }
         }
-        GameState::GameOver => space_ship.stop(),
     }
 }
 
+/// Turns with full deflection until almost facing the target.
+fn steer_toward(space_ship: &SpaceShip, transform: &Transform, target: Vec2) -> f32 {
+    let forward = (space_ship.rot_quat() * Vec3::Y).truncate();
+    let to_target = target - transform.translation.truncate();
+    if to_target == Vec2::ZERO {
+        return 0.0;
+    }
+
+    // Positive steering turns right (clockwise), i.e. toward negative angles.
+    let angle = forward.angle_between(to_target);
+    (-angle / 0.1).clamp(-1.0, 1.0)
+}
+
 fn dead(
     mut explosions: EventWriter<SpawnExplosion>,
+    mut deaths: EventWriter<PlayerDied>,
     mut players: Query<
         (
+            Entity,
             &mut Transform,
             &mut Velocity,
             &Collider,
             &mut Health,
-            &SpaceShip,
+            &Appearance,
+            &mut Interpolated,
         ),
         With<Player>,
     >,
@@ -112,9 +197,12 @@ fn dead(
         (&Transform, &Collider),
         (Without<Player>, Or<(With<Planet>, With<Star>)>),
     >,
+    mut rng: ResMut<GameRng>,
     level: Res<Level>,
 ) {
-    for (mut transform, mut velocity, collider, mut health, space_ship) in &mut players {
+    for (entity, mut transform, mut velocity, collider, mut health, appearance, mut interpolated) in
+        &mut players
+    {
         let mut dead = health.current() <= 0.0;
 
         if !dead {
@@ -129,16 +217,17 @@ fn dead(
         }
 
         if dead {
+            deaths.send(PlayerDied { player: entity });
             explosions.send(SpawnExplosion {
                 position: transform.translation,
-                material: space_ship.material(),
+                appearance: *appearance,
                 kind: ExplosionKind::Medium,
             });
 
             let respawn_pos = 'respawn: loop {
                 let respawn_pos = Vec3::new(
-                    rand::thread_rng().gen_range(-500.0..=500.0),
-                    rand::thread_rng().gen_range(-500.0..=500.0),
+                    rng.gen_range(-500.0..=500.0),
+                    rng.gen_range(-500.0..=500.0),
                     0.0,
                 );
                 if respawn_pos.length() > 500.0 {
@@ -156,6 +245,7 @@ fn dead(
 
             transform.translation = respawn_pos;
             transform.scale = Vec3::splat(5.0);
+            interpolated.snap();
             **velocity = Vec3::ZERO;
             *health = level.player_health;
         }
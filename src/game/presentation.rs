@@ -0,0 +1,107 @@
+use super::{Appearance, Player, ShotFired, SpaceShip};
+use crate::{
+    assets::{AudioAssets, GameAssets},
+    mixer::{AudioChannel, ChannelAudioBundle, SoundVolume},
+    AppState,
+};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+    sprite::Mesh2dHandle,
+};
+
+/// Shows the objects of the simulation and plays their sounds.
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        // Update
+        app.add_systems(
+            Update,
+            (add_visuals, update_engine_sounds, play_shot_sounds).run_if(in_state(AppState::Game)),
+        );
+    }
+}
+
+/// The material of an object, also used for the particles of its explosion.
+pub fn material(
+    appearance: Appearance,
+    assets: &GameAssets,
+    materials: &mut Assets<ColorMaterial>,
+) -> Handle<ColorMaterial> {
+    match appearance {
+        Appearance::Player(index) => assets.player_space_ship_materials[index].clone(),
+        Appearance::PlayerBullet(index) => assets.player_bullet_materials[index].clone(),
+        Appearance::Enemy => assets.enemy_space_ship_material.clone(),
+        Appearance::EnemyBullet => assets.enemy_bullet_material.clone(),
+        Appearance::Home => assets.home_planet_material.clone(),
+        Appearance::Planet(color) | Appearance::Star(color) => materials.add(color),
+    }
+}
+
+fn mesh(appearance: Appearance, assets: &GameAssets) -> Mesh2dHandle {
+    match appearance {
+        Appearance::Player(_) | Appearance::Enemy => assets.space_ship_mesh.clone(),
+        Appearance::PlayerBullet(_) | Appearance::EnemyBullet => assets.bullet_mesh.clone(),
+        Appearance::Home | Appearance::Planet(_) => assets.planet_mesh.clone(),
+        Appearance::Star(_) => assets.star_mesh.clone(),
+    }
+}
+
+fn add_visuals(
+    mut commands: Commands,
+    objects: Query<(Entity, &Appearance), Added<Appearance>>,
+    audio_assets: Res<AudioAssets>,
+    assets: Res<GameAssets>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, appearance) in &objects {
+        let mut entity = commands.entity(entity);
+        entity.insert((
+            mesh(*appearance, &assets),
+            material(*appearance, &assets, &mut materials),
+            VisibilityBundle::default(),
+        ));
+
+        if let Appearance::Player(_) | Appearance::Enemy = appearance {
+            entity.insert(ChannelAudioBundle::new(
+                AudioChannel::Engine,
+                audio_assets.thruster_fire_000.clone(),
+                PlaybackSettings {
+                    mode: PlaybackMode::Loop,
+                    volume: Volume::new(0.0),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn update_engine_sounds(mut space_ships: Query<(&SpaceShip, &mut SoundVolume, Has<Player>)>) {
+    for (space_ship, mut sound_volume, player) in &mut space_ships {
+        let volume = if player { 0.45 } else { 0.15 };
+        sound_volume.set_if_neq(SoundVolume(volume * space_ship.throttle.clamp(0.0, 1.0)));
+    }
+}
+
+fn play_shot_sounds(
+    mut commands: Commands,
+    mut shots: EventReader<ShotFired>,
+    players: Query<(), With<Player>>,
+    audio_assets: Res<AudioAssets>,
+) {
+    for shot in shots.read() {
+        commands.spawn(ChannelAudioBundle::new(
+            AudioChannel::Sfx,
+            if players.contains(shot.shooter) {
+                audio_assets.laser_small_001.clone()
+            } else {
+                audio_assets.laser_small_002.clone()
+            },
+            PlaybackSettings {
+                mode: PlaybackMode::Despawn,
+                ..default()
+            },
+        ));
+    }
+}
@@ -1,5 +1,8 @@
-use super::ApplyVelocity;
-use crate::AppState;
+use crate::{
+    actions::{Action, ActionState},
+    settings::{key_name, Settings},
+    AppState,
+};
 use bevy::prelude::*;
 
 pub struct QuitPlugin;
@@ -11,10 +14,7 @@ impl Plugin for QuitPlugin {
         app.add_systems(OnExit(AppState::Game), cleanup);
 
         // Update
-        app.add_systems(
-            Update,
-            update.after(ApplyVelocity).run_if(in_state(AppState::Game)),
-        );
+        app.add_systems(Update, update.run_if(in_state(AppState::Game)));
     }
 }
 
@@ -36,8 +36,11 @@ fn update(
     mut quit_ui_text: Query<&mut Text, With<QuitUiText>>,
     mut quit_ui_progress: Query<&mut Style, With<QuitUiProgress>>,
 
-    time: Res<Time>,
+    // Real time, so quitting also works while the game is paused.
+    time: Res<Time<Real>>,
+    actions: Res<ActionState>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    settings: Res<Settings>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     let Ok(mut visibility) = quit_ui.get_single_mut() else {
@@ -50,16 +53,17 @@ fn update(
         return;
     };
 
-    if keyboard_input.pressed(KeyCode::Escape) || keyboard_input.pressed(KeyCode::Backspace) {
+    if actions.pressed(Action::Quit) {
         if quit_timer.0.tick(time.delta()).just_finished() {
             next_state.set(AppState::MainMenu);
         } else {
             *visibility = Visibility::Inherited;
-            text.sections[0].value = if keyboard_input.pressed(KeyCode::Escape) {
-                "Hold ESC to quit".to_string()
-            } else {
-                "Hold BACKSPACE to quit".to_string()
+            // Held with a gamepad if no bound key is pressed.
+            let key = match settings.bindings.quit.pressed_key(&keyboard_input) {
+                Some(key) => key_name(key),
+                None => "SELECT".to_string(),
             };
+            text.sections[0].value = format!("Hold {key} to quit");
             style.width = Val::Px(300.0 * quit_timer.0.fraction_remaining());
         }
     } else {
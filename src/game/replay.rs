@@ -0,0 +1,378 @@
+use super::{playing, GameMode, GameState, Level, Player, PlayerInput, ReadInput, Seed};
+use crate::{storage, ui, AppState};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Records the input of each run and plays back replays.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(OnEnter(AppState::Game), setup);
+        app.add_systems(OnExit(AppState::Game), cleanup);
+
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            play_back
+                .in_set(ReadInput)
+                .run_if(in_state(AppState::Game))
+                .run_if(resource_exists::<ReplayPlayback>),
+        );
+        app.add_systems(
+            FixedUpdate,
+            record
+                .after(ReadInput)
+                .run_if(in_state(AppState::Game))
+                .run_if(playing)
+                .run_if(resource_exists::<ReplayRecorder>),
+        );
+    }
+}
+
+/// Stores the replays of the last finished runs to watch them from the main menu, with speed controls
+/// while watching.
+pub struct ReplayControlsPlugin;
+
+impl Plugin for ReplayControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Replays::load());
+
+        // Setup and cleanup
+        app.add_systems(
+            OnEnter(AppState::Game),
+            setup_controls.run_if(resource_exists::<ReplayPlayback>),
+        );
+        app.add_systems(OnExit(AppState::Game), cleanup_controls);
+        app.add_systems(
+            OnEnter(GameState::GameWon),
+            save.run_if(resource_exists::<ReplayRecorder>),
+        );
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            save.run_if(resource_exists::<ReplayRecorder>),
+        );
+
+        // Update
+        app.add_systems(
+            Update,
+            change_speed
+                .run_if(in_state(AppState::Game))
+                .run_if(resource_exists::<ReplayPlayback>),
+        );
+    }
+}
+
+/// The input of a run, which plays out the same again with the same level, mode and seed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    /// The name of the level.
+    pub level: String,
+    pub mode: GameMode,
+    pub seed: u64,
+    /// Simulation steps until the game was won or lost.
+    pub steps: u64,
+    /// The input of each player whenever it changed.
+    pub inputs: Vec<ReplayInput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReplayInput {
+    pub step: u64,
+    pub player: usize,
+    pub input: PlayerInput,
+}
+
+/// Replays kept in storage. Storing another one removes the oldest.
+const MAX_REPLAYS: usize = 5;
+
+/// The storage directory of the replays, each stored under its number.
+const REPLAYS_DIR: &str = "replays";
+
+/// The stored replays of the last finished runs, newest first, to watch them from the main menu.
+#[derive(Debug, Default, Resource)]
+pub struct Replays(pub Vec<StoredReplay>);
+
+#[derive(Debug, Clone)]
+pub struct StoredReplay {
+    /// Counts up with each stored replay.
+    pub number: u64,
+    pub replay: Replay,
+}
+
+impl Replays {
+    /// Loads the replays in storage, skipping unreadable ones.
+    pub fn load() -> Self {
+        let mut replays: Vec<_> = storage::names(REPLAYS_DIR)
+            .into_iter()
+            .filter_map(|name| {
+                let number = name.parse().ok()?;
+                let replay = storage::load(&replay_key(number))?;
+                Some(StoredReplay { number, replay })
+            })
+            .collect();
+        replays.sort_by_key(|stored| std::cmp::Reverse(stored.number));
+        Self(replays)
+    }
+
+    pub fn get(&self, number: u64) -> Option<&Replay> {
+        self.0
+            .iter()
+            .find(|stored| stored.number == number)
+            .map(|stored| &stored.replay)
+    }
+
+    /// Stores the replay under the next number, removing the oldest beyond [`MAX_REPLAYS`].
+    fn add(&mut self, replay: Replay) {
+        let number = self.0.first().map_or(1, |newest| newest.number + 1);
+        storage::save(&replay_key(number), &replay);
+        self.0.insert(0, StoredReplay { number, replay });
+        if self.0.len() > MAX_REPLAYS {
+            for oldest in self.0.drain(MAX_REPLAYS..) {
+                storage::remove(&replay_key(oldest.number));
+            }
+        }
+    }
+}
+
+fn replay_key(number: u64) -> String {
+    format!("{REPLAYS_DIR}/{number}")
+}
+
+/// Records the input of the current run, unless a replay is played back.
+#[derive(Debug, Default, Resource)]
+pub struct ReplayRecorder {
+    step: u64,
+    inputs: Vec<ReplayInput>,
+    last: [PlayerInput; 2],
+}
+
+impl ReplayRecorder {
+    fn record(&mut self, player: usize, input: PlayerInput) {
+        if self.last[player] != input {
+            self.last[player] = input;
+            self.inputs.push(ReplayInput {
+                step: self.step,
+                player,
+                input,
+            });
+        }
+    }
+
+    /// The replay of the input recorded so far.
+    pub fn replay(&self, level: String, mode: GameMode, seed: u64) -> Replay {
+        Replay {
+            level,
+            mode,
+            seed,
+            steps: self.step,
+            inputs: self.inputs.clone(),
+        }
+    }
+}
+
+/// Plays back a replay instead of reading the input devices. Inserted before starting the
+/// replay's level, and kept to watch it again on retry.
+#[derive(Debug, Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    step: u64,
+    next: usize,
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            step: 0,
+            next: 0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The input changes of the next simulation step.
+    fn next_step(&mut self) -> &[ReplayInput] {
+        let start = self.next;
+        let inputs = &self.replay.inputs[start..];
+        self.next += inputs
+            .iter()
+            .take_while(|input| input.step <= self.step)
+            .count();
+        self.step += 1;
+        &self.replay.inputs[start..self.next]
+    }
+}
+
+/// Playback speeds, as relative speed of virtual time.
+const SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+#[derive(Debug, Component)]
+enum ButtonAction {
+    Slower,
+    Faster,
+}
+
+#[derive(Debug, Component)]
+struct SpeedText;
+
+fn record(mut recorder: ResMut<ReplayRecorder>, players: Query<(&Player, &PlayerInput)>) {
+    for (player, input) in &players {
+        recorder.record(player.index, *input);
+    }
+    recorder.step += 1;
+}
+
+fn play_back(
+    mut playback: ResMut<ReplayPlayback>,
+    mut players: Query<(&Player, &mut PlayerInput)>,
+) {
+    for change in playback.next_step() {
+        for (player, mut input) in &mut players {
+            if player.index == change.player {
+                *input = change.input;
+            }
+        }
+    }
+}
+
+fn save(
+    mut commands: Commands,
+    recorder: Res<ReplayRecorder>,
+    level: Res<Level>,
+    mode: Res<GameMode>,
+    seed: Res<Seed>,
+    mut replays: ResMut<Replays>,
+) {
+    replays.add(recorder.replay(level.name.clone(), *mode, **seed));
+    commands.remove_resource::<ReplayRecorder>();
+}
+
+fn change_speed(
+    interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
+    mut time: ResMut<Time<Virtual>>,
+    mut texts: Query<&mut Text, With<SpeedText>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let current = SPEEDS
+            .iter()
+            .position(|speed| *speed == time.relative_speed())
+            .unwrap_or(2);
+        let speed = match action {
+            ButtonAction::Slower => SPEEDS[current.saturating_sub(1)],
+            ButtonAction::Faster => SPEEDS[(current + 1).min(SPEEDS.len() - 1)],
+        };
+        time.set_relative_speed(speed);
+        for mut text in &mut texts {
+            text.sections[0].value = speed_label(speed);
+        }
+    }
+}
+
+fn speed_label(speed: f32) -> String {
+    format!("REPLAY {speed}x")
+}
+
+fn setup(mut commands: Commands, playback: Option<ResMut<ReplayPlayback>>) {
+    match playback {
+        // Start over, e.g. when watching the replay again.
+        Some(mut playback) => {
+            playback.step = 0;
+            playback.next = 0;
+        }
+        None => commands.insert_resource(ReplayRecorder::default()),
+    }
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<ReplayRecorder>();
+}
+
+fn setup_controls(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(10.0),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            ui::spawn_button_with(parent, "Slower", ButtonAction::Slower);
+            parent.spawn((
+                TextBundle::from_section(
+                    speed_label(1.0),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::srgb(0.9, 0.9, 0.9),
+                        ..default()
+                    },
+                ),
+                SpeedText,
+            ));
+            ui::spawn_button_with(parent, "Faster", ButtonAction::Faster);
+        });
+}
+
+fn cleanup_controls(mut time: ResMut<Time<Virtual>>) {
+    time.set_relative_speed(1.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_back_recorded_input() {
+        let idle = PlayerInput::default();
+        let turning = PlayerInput {
+            steer: -1.0,
+            ..default()
+        };
+        let firing = PlayerInput {
+            fire: true,
+            ..default()
+        };
+        let steps = [
+            [idle, idle],
+            [turning, idle],
+            [turning, idle],
+            [turning, firing],
+            [idle, firing],
+        ];
+
+        let mut recorder = ReplayRecorder::default();
+        for step in &steps {
+            for (player, input) in step.iter().enumerate() {
+                recorder.record(player, *input);
+            }
+            recorder.step += 1;
+        }
+        let replay = recorder.replay("Test".to_string(), GameMode::Coop, 42);
+        assert_eq!(replay.steps, 5);
+        assert_eq!(replay.inputs.len(), 3);
+
+        let mut playback = ReplayPlayback::new(replay);
+        let mut inputs = [PlayerInput::default(); 2];
+        for step in &steps {
+            for change in playback.next_step() {
+                inputs[change.player] = change.input;
+            }
+            assert_eq!(inputs, *step);
+        }
+    }
+}
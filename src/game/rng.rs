@@ -0,0 +1,97 @@
+use super::ReplayPlayback;
+use crate::AppState;
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NextSeed>();
+
+        app.configure_sets(
+            FixedUpdate,
+            (
+                UseGameRng::SpawnEnemies,
+                UseGameRng::UpdateEnemies,
+                UseGameRng::RespawnPlayers,
+            )
+                .chain(),
+        );
+
+        // Setup and cleanup
+        app.add_systems(OnEnter(AppState::Game), setup);
+        app.add_systems(OnExit(AppState::Game), cleanup);
+    }
+}
+
+/// The seed of the next runs, or `None` for a random seed each run. Set in the main menu or
+/// with the `--seed` command line argument.
+#[derive(Debug, Clone, Copy, Default, Resource)]
+pub struct NextSeed(pub Option<u64>);
+
+/// The seed of the current run.
+#[derive(Debug, Clone, Copy, Deref, Resource)]
+pub struct Seed(pub u64);
+
+/// The random number generator of all gameplay systems, so a run with the same [`Seed`] and the
+/// same input plays out the same.
+#[derive(Debug, Deref, DerefMut, Resource)]
+pub struct GameRng(StdRng);
+
+/// The systems drawing from [`GameRng`], one after the other. Ambiguous systems could draw in a
+/// different order each run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum UseGameRng {
+    SpawnEnemies,
+    UpdateEnemies,
+    RespawnPlayers,
+}
+
+fn setup(mut commands: Commands, next_seed: Res<NextSeed>, playback: Option<Res<ReplayPlayback>>) {
+    let seed = match playback {
+        Some(playback) => playback.replay().seed,
+        None => next_seed.0.unwrap_or_else(rand::random),
+    };
+    info!("Starting run with seed {seed}");
+    commands.insert_resource(Seed(seed));
+    commands.insert_resource(GameRng(StdRng::seed_from_u64(seed)));
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<Seed>();
+    commands.remove_resource::<GameRng>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game::{GameMode, Level},
+        headless::Simulation,
+    };
+
+    #[test]
+    fn game_rng_is_drawn_from_in_a_fixed_order() {
+        let level = Level::from_ron(include_bytes!("../../assets/levels/easy.level.ron")).unwrap();
+        let mut simulation = Simulation::new(level, GameMode::Coop, 1);
+        simulation.step();
+
+        let world = simulation.world_mut();
+        let rng = world.components().resource_id::<GameRng>().unwrap();
+        let schedules = world.resource::<Schedules>();
+        let schedule = schedules.get(FixedUpdate).unwrap();
+        let name = |node| {
+            let mut systems = schedule.systems().unwrap();
+            systems.find(|(id, _)| *id == node).unwrap().1.name()
+        };
+        let ambiguous = schedule
+            .graph()
+            .conflicting_systems()
+            .iter()
+            .filter(|(_, _, conflicts)| conflicts.contains(&rng))
+            .map(|(a, b, _)| format!("{} and {}", name(*a), name(*b)))
+            .collect::<Vec<_>>();
+        assert!(ambiguous.is_empty(), "{ambiguous:#?}");
+    }
+}
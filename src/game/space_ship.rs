@@ -1,25 +1,22 @@
 use super::{
-    ApplyVelocity, BulletBundle, BulletMissileLock, Collider, KeepInMap, MaxVelocity, Velocity,
-};
-use crate::{
-    assets::{AudioAssets, GameAssets},
-    AppState,
-};
-use bevy::{
-    audio::{PlaybackMode, Volume},
-    prelude::*,
-    sprite::MaterialMesh2dBundle,
+    Appearance, ApplyVelocity, BulletBundle, BulletMissileLock, Collider, Interpolated, KeepInMap,
+    MaxVelocity, ReadInput, Velocity,
 };
+use crate::AppState;
+use bevy::prelude::*;
 
 pub struct SpaceShipPlugin;
 
 impl Plugin for SpaceShipPlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        app.add_event::<ShotFired>();
+
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             update
                 .in_set(UpdateSpaceShip)
+                .after(ReadInput)
                 .before(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
         );
@@ -29,43 +26,57 @@ impl Plugin for SpaceShipPlugin {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
 pub struct UpdateSpaceShip;
 
+#[derive(Debug, Event)]
+pub struct ShotFired {
+    pub shooter: Entity,
+}
+
 #[derive(Debug, Component)]
 pub struct SpaceShip {
     rotation: f32,
-    material: Handle<ColorMaterial>,
-    bullet_material: Handle<ColorMaterial>,
-    pub throttle: bool,
+    bullet_appearance: Appearance,
+    /// From 0 (off) to 1 (full thrust).
+    pub throttle: f32,
     pub brake: bool,
-    pub steering: Steering,
+    /// From -1 (full left) to 1 (full right).
+    pub steer: f32,
     pub shoot: Option<f32>,
     pub shoot_missile_lock: Option<Entity>,
 }
 
 impl SpaceShip {
-    pub fn material(&self) -> Handle<ColorMaterial> {
-        self.material.clone()
-    }
-
     pub fn stop(&mut self) {
-        self.throttle = false;
+        self.throttle = 0.0;
         self.brake = true;
-        self.steering = Steering::None;
+        self.steer = 0.0;
         self.shoot = None;
         self.shoot_missile_lock = None;
     }
 }
 
-#[derive(Debug)]
-pub enum Steering {
-    Left,
-    Right,
-    None,
-}
-
 impl SpaceShip {
     pub fn rot_quat(&self) -> Quat {
         Quat::from_rotation_z(self.rotation)
     }
+
+    pub fn set_rotation(&mut self, rotation: f32) {
+        self.rotation = rotation;
+    }
+
+    /// How far to turn to face the direction, in degrees, positive to the right.
+    pub fn angle_to(&self, direction: Vec3) -> f32 {
+        direction
+            .normalize()
+            .angle_between(self.rot_quat() * -Vec3::X)
+            .to_degrees()
+            - 90.0
+    }
+
+    /// The steering to face the direction. Full deflection beyond 2.5 degrees, less when almost
+    /// aligned to not overshoot.
+    pub fn steer_toward(&self, direction: Vec3) -> f32 {
+        (self.angle_to(direction) / 2.5).clamp(-1.0, 1.0)
+    }
 }
 
 #[derive(Bundle)]
@@ -75,8 +86,9 @@ pub struct SpaceShipBundle {
     pub velocity: Velocity,
     pub max_velocity: MaxVelocity,
     pub keep_in_map: KeepInMap,
-    pub mesh: MaterialMesh2dBundle<ColorMaterial>,
-    pub audio: AudioBundle,
+    pub transform: TransformBundle,
+    pub interpolated: Interpolated,
+    pub appearance: Appearance,
 }
 
 impl SpaceShipBundle {
@@ -85,19 +97,15 @@ impl SpaceShipBundle {
         velocity: Velocity,
         position: Vec3,
         rotation: f32,
-        material: Handle<ColorMaterial>,
-        bullet_material: Handle<ColorMaterial>,
-
-        audio_assets: &AudioAssets,
-        assets: &GameAssets,
+        appearance: Appearance,
+        bullet_appearance: Appearance,
     ) -> Self {
         let space_ship = SpaceShip {
             rotation,
-            material: material.clone(),
-            bullet_material,
-            throttle: false,
+            bullet_appearance,
+            throttle: 0.0,
             brake: false,
-            steering: Steering::None,
+            steer: 0.0,
             shoot: None,
             shoot_missile_lock: None,
         };
@@ -109,22 +117,11 @@ impl SpaceShipBundle {
             velocity,
             max_velocity: MaxVelocity(180.0),
             keep_in_map: KeepInMap,
-            mesh: MaterialMesh2dBundle {
-                mesh: assets.space_ship_mesh.clone(),
-                material,
-                transform: Transform::from_translation(position)
-                    .with_rotation(space_ship.rot_quat()),
-
-                ..default()
-            },
-            audio: AudioBundle {
-                source: audio_assets.thruster_fire_000.clone(),
-                settings: PlaybackSettings {
-                    mode: PlaybackMode::Loop,
-                    volume: Volume::new(0.0),
-                    ..default()
-                },
-            },
+            transform: TransformBundle::from_transform(
+                Transform::from_translation(position).with_rotation(space_ship.rot_quat()),
+            ),
+            interpolated: Interpolated::default(),
+            appearance,
             space_ship,
         }
     }
@@ -133,32 +130,26 @@ impl SpaceShipBundle {
 fn update(
     mut commands: Commands,
     time: Res<Time>,
-    global_volume: Res<GlobalVolume>,
+    mut shots: EventWriter<ShotFired>,
     mut space_ships: Query<(
+        Entity,
         &Collider,
         &mut SpaceShip,
         &mut Velocity,
         &mut Transform,
-        Option<&AudioSink>,
     )>,
-
-    audio_assets: Res<AudioAssets>,
-    assets: Res<GameAssets>,
 ) {
-    for (collider, mut space_ship, mut velocity, mut transform, audio) in &mut space_ships {
-        space_ship.rotation += match space_ship.steering {
-            Steering::Left => 3.0 * time.delta_seconds(),
-            Steering::Right => -3.0 * time.delta_seconds(),
-            Steering::None => 0.0,
-        };
+    for (entity, collider, mut space_ship, mut velocity, mut transform) in &mut space_ships {
+        let steer = space_ship.steer.clamp(-1.0, 1.0);
+        let throttle = space_ship.throttle.clamp(0.0, 1.0);
 
-        if space_ship.throttle {
-            **velocity += space_ship.rot_quat()
-                * Vec3::new(0.0, 1.0, 0.0)
-                * 300.0
-                * 1.0
-                * time.delta_seconds();
-        }
+        space_ship.rotation -= steer * 3.0 * time.delta_seconds();
+
+        **velocity += space_ship.rot_quat()
+            * Vec3::new(0.0, 1.0, 0.0)
+            * 300.0
+            * throttle
+            * time.delta_seconds();
         if space_ship.brake {
             let brake = if velocity.length() < 50.0 {
                 **velocity * 0.99 * time.delta_seconds()
@@ -171,57 +162,22 @@ fn update(
         if let Some(damage) = space_ship.shoot.take() {
             let mut cmds = commands.spawn((
                 BulletBundle::new(
+                    entity,
                     collider.group ^ u32::MAX,
                     damage,
                     20.0,
                     Velocity(space_ship.rot_quat() * Vec3::new(0.0, 256.0, 0.0)),
                     transform.translation + space_ship.rot_quat() * Vec3::new(0.0, 10.0, 0.0),
-                    space_ship.bullet_material.clone(),
-                    &assets,
+                    space_ship.bullet_appearance,
                 ),
                 StateScoped(AppState::Game),
             ));
             if let Some(target) = space_ship.shoot_missile_lock {
                 cmds.insert(BulletMissileLock { target });
             }
-            commands.spawn(AudioBundle {
-                source: if collider.group & 0b1 != 0 {
-                    audio_assets.laser_small_001.clone()
-                } else {
-                    audio_assets.laser_small_002.clone()
-                },
-                settings: PlaybackSettings {
-                    mode: PlaybackMode::Despawn,
-                    ..default()
-                },
-            });
+            shots.send(ShotFired { shooter: entity });
         }
 
         transform.rotation = space_ship.rot_quat();
-
-        if let Some(audio) = audio {
-            let mut volume = if collider.group & 0b1 != 0 {
-                // Player
-                if space_ship.throttle {
-                    0.45
-                } else {
-                    0.0
-                }
-            } else {
-                // Enemy
-                if space_ship.throttle {
-                    0.15
-                } else {
-                    0.0
-                }
-            };
-
-            // TODO: Hack, since audio.volume seems to be absolute and not relative to global volume.
-            volume *= *global_volume.volume;
-
-            if audio.volume() != volume {
-                audio.set_volume(volume);
-            }
-        }
     }
 }
@@ -1,7 +1,5 @@
-use crate::assets::GameAssets;
-
-use super::{gravity::Mass, Collider};
-use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use super::{gravity::Mass, Appearance, Collider};
+use bevy::prelude::*;
 
 pub struct StarPlugin;
 
@@ -9,6 +7,8 @@ impl Plugin for StarPlugin {
     fn build(&self, _app: &mut App) {}
 }
 
+pub const STAR_RADIUS: f32 = 16.0;
+
 #[derive(Debug, Component)]
 pub struct Star;
 
@@ -17,29 +17,21 @@ pub struct StarBundle {
     pub star: Star,
     pub collider: Collider,
     pub mass: Mass,
-    pub mesh: MaterialMesh2dBundle<ColorMaterial>,
+    pub transform: TransformBundle,
+    pub appearance: Appearance,
 }
 
 impl StarBundle {
-    pub fn new(
-        mass: Mass,
-        color: Color,
-
-        assets: &GameAssets,
-        materials: &mut Assets<ColorMaterial>,
-    ) -> Self {
+    pub fn new(mass: Mass, color: Color) -> Self {
         Self {
             star: Star,
             collider: Collider {
-                radius: 16.0,
+                radius: STAR_RADIUS,
                 group: 0b100,
             },
             mass,
-            mesh: MaterialMesh2dBundle {
-                mesh: assets.star_mesh.clone(),
-                material: materials.add(color),
-                ..default()
-            },
+            transform: TransformBundle::default(),
+            appearance: Appearance::Star(color),
         }
     }
 }
@@ -0,0 +1,134 @@
+use super::{
+    enemy::Enemy, playing, BulletHit, Health, Home, Player, PlayerDied, ShotFired, UpdateBullets,
+    UpdateSpaceShip,
+};
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(OnEnter(AppState::Game), setup);
+        app.add_systems(OnExit(AppState::Game), cleanup);
+
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            record
+                .in_set(RecordStats)
+                .after(UpdateBullets)
+                .after(UpdateSpaceShip)
+                .run_if(in_state(AppState::Game))
+                .run_if(playing),
+        );
+        app.add_systems(
+            FixedUpdate,
+            track_time.run_if(in_state(AppState::Game)).run_if(playing),
+        );
+    }
+}
+
+/// Records the [`RunStats`] until the run is decided. Runs before the systems deciding it, so the
+/// deciding step still counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct RecordStats;
+
+/// Statistics of the current run, from the player's point of view.
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub struct RunStats {
+    /// Enemies killed by the player's bullets.
+    pub kills: u32,
+    pub shots_fired: u32,
+    /// Player bullets that hit an enemy.
+    pub hits: u32,
+    pub deaths: u32,
+    pub home_damage_taken: f32,
+    pub time_survived: f32,
+    /// Fraction of the home planet's health that is left.
+    pub home_health: f32,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            kills: 0,
+            shots_fired: 0,
+            hits: 0,
+            deaths: 0,
+            home_damage_taken: 0.0,
+            time_survived: 0.0,
+            home_health: 1.0,
+        }
+    }
+}
+
+impl RunStats {
+    pub fn accuracy(&self) -> f32 {
+        match self.shots_fired {
+            0 => 0.0,
+            shots_fired => self.hits as f32 / shots_fired as f32,
+        }
+    }
+
+    pub fn score(&self) -> u32 {
+        self.kills * 100 + (self.time_survived * 10.0) as u32 + (self.home_health * 1000.0) as u32
+    }
+}
+
+pub fn format_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn record(
+    mut stats: ResMut<RunStats>,
+    mut shots: EventReader<ShotFired>,
+    mut hits: EventReader<BulletHit>,
+    mut deaths: EventReader<PlayerDied>,
+    players: Query<(), With<Player>>,
+    enemies: Query<(), With<Enemy>>,
+    homes: Query<&Health, With<Home>>,
+) {
+    for shot in shots.read() {
+        if players.contains(shot.shooter) {
+            stats.shots_fired += 1;
+        }
+    }
+
+    for hit in hits.read() {
+        if players.contains(hit.shooter) && enemies.contains(hit.target) {
+            stats.hits += 1;
+            if hit.killed {
+                stats.kills += 1;
+            }
+        }
+        if homes.contains(hit.target) {
+            stats.home_damage_taken += hit.damage;
+        }
+    }
+
+    for death in deaths.read() {
+        if players.contains(death.player) {
+            stats.deaths += 1;
+        }
+    }
+
+    stats.home_health = match homes.get_single() {
+        Ok(health) => health.fraction(),
+        Err(_) => 0.0,
+    };
+}
+
+fn track_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    stats.time_survived += time.delta_seconds();
+}
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(RunStats::default());
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<RunStats>();
+}
@@ -5,9 +5,9 @@ pub struct VelocityPlugin;
 
 impl Plugin for VelocityPlugin {
     fn build(&self, app: &mut App) {
-        // Update
+        // Fixed update
         app.add_systems(
-            Update,
+            FixedUpdate,
             apply_velocity
                 .in_set(ApplyVelocity)
                 .run_if(in_state(AppState::Game)),
@@ -0,0 +1,151 @@
+use super::{
+    playing, stats::RecordStats, ApplyGravity, Bullet, GameMode, GameState, Health, Interpolated,
+    KillPlayers, Level, Player, PlayerDied, SpaceShip, Velocity, PLAYER_STARTS,
+};
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup and cleanup
+        app.add_systems(
+            OnEnter(AppState::Game),
+            setup.run_if(resource_equals(GameMode::Versus)),
+        );
+        app.add_systems(OnExit(AppState::Game), cleanup);
+
+        // Fixed update
+        app.add_systems(
+            FixedUpdate,
+            score_rounds
+                .after(KillPlayers)
+                .after(RecordStats)
+                .before(ApplyGravity)
+                .run_if(in_state(AppState::Game))
+                .run_if(playing)
+                .run_if(resource_exists::<VersusScore>),
+        );
+    }
+}
+
+/// Rounds a player has to win to win the match.
+pub const ROUNDS_TO_WIN: u32 = 3;
+
+/// The rounds won by each player in versus mode.
+#[derive(Debug, Default, Resource)]
+pub struct VersusScore {
+    pub wins: [u32; 2],
+    pub rounds: u32,
+}
+
+impl VersusScore {
+    /// The index of the player who won the match.
+    pub fn winner(&self) -> Option<usize> {
+        let leader = self.leader();
+        (self.wins[leader] >= ROUNDS_TO_WIN).then_some(leader)
+    }
+
+    /// The index of the player who won more rounds, the first player if both won as many. The
+    /// match is only over once there is a [`VersusScore::winner`], so then this is the winner.
+    pub fn leader(&self) -> usize {
+        if self.wins[1] > self.wins[0] {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Scores the round lost by the given players. Nobody scores if both are lost at once.
+    fn lost_round(&mut self, losers: &[usize]) {
+        if let [loser] = losers {
+            self.wins[1 - loser] += 1;
+        }
+        self.rounds += 1;
+    }
+}
+
+/// A round ends when a player dies. Then both players start over, or the match is over.
+fn score_rounds(
+    mut commands: Commands,
+    mut deaths: EventReader<PlayerDied>,
+    mut score: ResMut<VersusScore>,
+    mut players: Query<(
+        &Player,
+        &mut Transform,
+        &mut Velocity,
+        &mut SpaceShip,
+        &mut Health,
+        &mut Interpolated,
+    )>,
+    bullets: Query<Entity, With<Bullet>>,
+    level: Res<Level>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    let mut losers = deaths
+        .read()
+        .filter_map(|death| Some(players.get(death.player).ok()?.0.index))
+        .collect::<Vec<_>>();
+    if losers.is_empty() {
+        return;
+    }
+    losers.sort();
+    losers.dedup();
+
+    score.lost_round(&losers);
+    if score.winner().is_some() {
+        next_state_game.set(GameState::GameOver);
+        return;
+    }
+
+    for (player, mut transform, mut velocity, mut space_ship, mut health, mut interpolated) in
+        &mut players
+    {
+        let (position, rotation) = PLAYER_STARTS[player.index];
+        transform.translation = position;
+        transform.scale = Vec3::splat(5.0);
+        interpolated.snap();
+        **velocity = Vec3::ZERO;
+        space_ship.set_rotation(rotation);
+        *health = level.player_health;
+    }
+    for bullet in &bullets {
+        commands.entity(bullet).despawn();
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.insert_resource(VersusScore::default());
+}
+
+fn cleanup(mut commands: Commands) {
+    commands.remove_resource::<VersusScore>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_to_win_enough_rounds_wins() {
+        let mut score = VersusScore::default();
+        score.lost_round(&[1]);
+        score.lost_round(&[0, 1]);
+        score.lost_round(&[1]);
+        assert_eq!(score.wins, [2, 0]);
+        assert_eq!(score.leader(), 0);
+        assert_eq!(score.winner(), None);
+
+        score.lost_round(&[1]);
+        assert_eq!(score.rounds, 4);
+        assert_eq!(score.winner(), Some(0));
+
+        let mut score = VersusScore::default();
+        for _ in 0..ROUNDS_TO_WIN {
+            score.lost_round(&[0]);
+        }
+        assert_eq!(score.leader(), 1);
+        assert_eq!(score.winner(), Some(1));
+    }
+}
@@ -0,0 +1,204 @@
+use bevy::{
+    ecs::system::SystemParam,
+    input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent},
+        InputSystem,
+    },
+    prelude::*,
+};
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GamepadSlots>();
+        app.add_systems(PreUpdate, assign_slots.after(InputSystem));
+    }
+}
+
+/// Which gamepad each player uses, by player index. A gamepad keeps its slot while connected, and
+/// a disconnected one leaves its slot empty for the next gamepad, so the other players keep theirs.
+#[derive(Debug, Default, Resource)]
+pub struct GamepadSlots(Vec<Option<Gamepad>>);
+
+impl GamepadSlots {
+    /// Assigns the gamepad to the first empty slot, returning its index.
+    fn connect(&mut self, gamepad: Gamepad) -> usize {
+        if let Some(index) = self.index(gamepad) {
+            return index;
+        }
+        match self.0.iter().position(Option::is_none) {
+            Some(index) => {
+                self.0[index] = Some(gamepad);
+                index
+            }
+            None => {
+                self.0.push(Some(gamepad));
+                self.0.len() - 1
+            }
+        }
+    }
+
+    fn disconnect(&mut self, gamepad: Gamepad) {
+        if let Some(index) = self.index(gamepad) {
+            self.0[index] = None;
+        }
+    }
+
+    fn index(&self, gamepad: Gamepad) -> Option<usize> {
+        self.0.iter().position(|slot| *slot == Some(gamepad))
+    }
+
+    pub fn get(&self, index: usize) -> Option<Gamepad> {
+        self.0.get(index).copied().flatten()
+    }
+}
+
+/// Combined input of all connected gamepads, so any of them can be used without selecting one.
+#[derive(SystemParam)]
+pub struct GamepadInput<'w> {
+    gamepads: Res<'w, Gamepads>,
+    slots: Res<'w, GamepadSlots>,
+    buttons: Res<'w, ButtonInput<GamepadButton>>,
+    axes: Res<'w, Axis<GamepadAxis>>,
+    button_axes: Res<'w, Axis<GamepadButton>>,
+}
+
+impl<'w> GamepadInput<'w> {
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.all().any(|gamepad| gamepad.pressed(button_type))
+    }
+
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.all().any(|gamepad| gamepad.just_pressed(button_type))
+    }
+
+    /// How far an analog button (e.g. a trigger) is pressed, from 0 to 1. The maximum of all gamepads.
+    pub fn value(&self, button_type: GamepadButtonType) -> f32 {
+        self.all()
+            .map(|gamepad| gamepad.value(button_type))
+            .fold(0.0, f32::max)
+    }
+
+    /// The left stick, or the d-pad if it is pressed. Positive is right and up.
+    pub fn direction(&self) -> Vec2 {
+        let dpad = Vec2::new(
+            self.pressed(GamepadButtonType::DPadRight) as i32 as f32
+                - self.pressed(GamepadButtonType::DPadLeft) as i32 as f32,
+            self.pressed(GamepadButtonType::DPadUp) as i32 as f32
+                - self.pressed(GamepadButtonType::DPadDown) as i32 as f32,
+        );
+        if dpad != Vec2::ZERO {
+            dpad
+        } else {
+            self.stick()
+        }
+    }
+
+    /// The left stick of the gamepad that pushes it the most. Positive is right and up.
+    pub fn stick(&self) -> Vec2 {
+        self.all()
+            .map(|gamepad| gamepad.stick())
+            .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+            .unwrap_or(Vec2::ZERO)
+    }
+
+    /// The gamepad in the given slot of [`GamepadSlots`], if one is connected there.
+    pub fn get(&self, index: usize) -> Option<SingleGamepad<'_, 'w>> {
+        let gamepad = self.slots.get(index)?;
+        Some(SingleGamepad {
+            input: self,
+            gamepad,
+        })
+    }
+
+    fn all(&self) -> impl Iterator<Item = SingleGamepad<'_, 'w>> {
+        self.gamepads.iter().map(|gamepad| SingleGamepad {
+            input: self,
+            gamepad,
+        })
+    }
+}
+
+/// The input of one gamepad, e.g. to give each player their own.
+pub struct SingleGamepad<'a, 'w> {
+    input: &'a GamepadInput<'w>,
+    gamepad: Gamepad,
+}
+
+impl SingleGamepad<'_, '_> {
+    pub fn pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.input
+            .buttons
+            .pressed(GamepadButton::new(self.gamepad, button_type))
+    }
+
+    pub fn just_pressed(&self, button_type: GamepadButtonType) -> bool {
+        self.input
+            .buttons
+            .just_pressed(GamepadButton::new(self.gamepad, button_type))
+    }
+
+    /// How far an analog button (e.g. a trigger) is pressed, from 0 to 1.
+    pub fn value(&self, button_type: GamepadButtonType) -> f32 {
+        self.input
+            .button_axes
+            .get(GamepadButton::new(self.gamepad, button_type))
+            .unwrap_or(0.0)
+    }
+
+    /// The left stick. Positive is right and up.
+    pub fn stick(&self) -> Vec2 {
+        Vec2::new(
+            self.axis(GamepadAxisType::LeftStickX),
+            self.axis(GamepadAxisType::LeftStickY),
+        )
+    }
+
+    fn axis(&self, axis_type: GamepadAxisType) -> f32 {
+        self.input
+            .axes
+            .get(GamepadAxis::new(self.gamepad, axis_type))
+            .unwrap_or(0.0)
+    }
+}
+
+fn assign_slots(mut events: EventReader<GamepadConnectionEvent>, mut slots: ResMut<GamepadSlots>) {
+    for event in events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                let index = slots.connect(event.gamepad);
+                info!(
+                    "Gamepad {} connected for player {}: {}",
+                    event.gamepad.id,
+                    index + 1,
+                    info.name
+                );
+            }
+            GamepadConnection::Disconnected => {
+                slots.disconnect(event.gamepad);
+                info!("Gamepad {} disconnected", event.gamepad.id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disconnecting_keeps_the_other_slots() {
+        let mut slots = GamepadSlots::default();
+        assert_eq!(slots.connect(Gamepad::new(0)), 0);
+        assert_eq!(slots.connect(Gamepad::new(1)), 1);
+
+        slots.disconnect(Gamepad::new(0));
+        assert_eq!(slots.get(0), None);
+        assert_eq!(slots.get(1), Some(Gamepad::new(1)));
+
+        assert_eq!(slots.connect(Gamepad::new(2)), 0);
+        assert_eq!(slots.connect(Gamepad::new(1)), 1);
+        assert_eq!(slots.get(0), Some(Gamepad::new(2)));
+    }
+}
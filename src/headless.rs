@@ -0,0 +1,174 @@
+//! Runs the game without a window, rendering, audio or input devices, one simulation step at a
+//! time, for automated gameplay tests.
+
+use crate::{
+    game::{
+        Bot, GameMode, GameState, Health, Home, Level, NextSeed, Player, PlayerInput, Replay,
+        ReplayPlayback, ReplayRecorder, RunStats, Seed, SimulationPlugin, VersusScore,
+        SIMULATION_RATE,
+    },
+    AppState,
+};
+use bevy::{asset::AssetPlugin, prelude::*, state::app::StatesPlugin, time::TimeUpdateStrategy};
+use std::{path::Path, time::Duration};
+
+/// A game of a level, advanced by hand. Each [`Simulation::step`] runs exactly one simulation
+/// step, so a run with the same level, mode, seed and input always plays out the same.
+pub struct Simulation {
+    app: App,
+}
+
+impl Simulation {
+    pub fn new(level: Level, mode: GameMode, seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), StatesPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+                1.0 / SIMULATION_RATE,
+            )));
+
+        app.insert_state(AppState::StartGame)
+            .enable_state_scoped_entities::<AppState>()
+            .add_plugins(SimulationPlugin);
+
+        app.insert_resource(level)
+            .insert_resource(mode)
+            .insert_resource(NextSeed(Some(seed)));
+
+        // Enters the game on the first step.
+        app.update();
+
+        Self { app }
+    }
+
+    /// Plays back the replay, which has to be of the given level.
+    pub fn from_replay(level: Level, replay: Replay) -> Self {
+        let (mode, seed) = (replay.mode, replay.seed);
+        let mut simulation = Self::new(level, mode, seed);
+        simulation.app.insert_resource(ReplayPlayback::new(replay));
+        simulation
+    }
+
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    pub fn steps(&mut self, steps: u64) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    /// Steps until the game is won or lost, and returns the number of steps taken, or `None` if
+    /// the game is still running after `max_steps`.
+    pub fn run_until_over(&mut self, max_steps: u64) -> Option<u64> {
+        for steps in 1..=max_steps {
+            self.step();
+            if matches!(self.state(), GameState::GameWon | GameState::GameOver) {
+                return Some(steps);
+            }
+        }
+        None
+    }
+
+    /// Sets the input of a player, kept until it is set again.
+    pub fn set_input(&mut self, player: usize, input: PlayerInput) {
+        let mut players = self.app.world_mut().query::<(&Player, &mut PlayerInput)>();
+        for (other, mut other_input) in players.iter_mut(self.app.world_mut()) {
+            if other.index == player {
+                *other_input = input;
+            }
+        }
+    }
+
+    /// Lets a [`Bot`] play the player from now on.
+    pub fn add_bot(&mut self, player: usize) {
+        let mut players = self.app.world_mut().query::<(Entity, &Player)>();
+        let entities = players
+            .iter(self.app.world())
+            .filter(|(_, other)| other.index == player)
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in entities {
+            self.app
+                .world_mut()
+                .entity_mut(entity)
+                .insert(Bot::default());
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        **self.app.world().resource::<State<GameState>>()
+    }
+
+    pub fn stats(&self) -> &RunStats {
+        self.app.world().resource::<RunStats>()
+    }
+
+    /// `None` once the home planet is destroyed, or in versus.
+    pub fn home_health(&mut self) -> Option<Health> {
+        let mut homes = self.app.world_mut().query_filtered::<&Health, With<Home>>();
+        homes.get_single(self.app.world()).ok().copied()
+    }
+
+    /// The rounds won by each player in versus, `None` in the other modes.
+    pub fn versus_wins(&self) -> Option<[u32; 2]> {
+        self.app
+            .world()
+            .get_resource::<VersusScore>()
+            .map(|score| score.wins)
+    }
+
+    /// The position of each player.
+    pub fn player_positions(&mut self) -> Vec<Vec3> {
+        let mut players = self.app.world_mut().query::<(&Player, &Transform)>();
+        let mut players = players
+            .iter(self.app.world())
+            .map(|(player, transform)| (player.index, transform.translation))
+            .collect::<Vec<_>>();
+        players.sort_by_key(|(index, _)| *index);
+        players.into_iter().map(|(_, position)| position).collect()
+    }
+
+    /// The input recorded so far, to play it back with [`Simulation::from_replay`].
+    pub fn replay(&self) -> Replay {
+        let world = self.app.world();
+        world.resource::<ReplayRecorder>().replay(
+            world.resource::<Level>().name.clone(),
+            *world.resource::<GameMode>(),
+            **world.resource::<Seed>(),
+        )
+    }
+
+    /// To query and change anything else.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+}
+
+/// Loads and validates all `*.level.ron` files in the directory, ordered by difficulty like in the
+/// main menu.
+pub fn load_levels(dir: impl AsRef<Path>) -> Result<Vec<Level>, String> {
+    let dir = dir.as_ref();
+    let entries = std::fs::read_dir(dir).map_err(|err| format!("{}: {err}", dir.display()))?;
+    let mut levels = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|err| format!("{}: {err}", dir.display()))?
+            .path();
+        if !path.to_string_lossy().ends_with(".level.ron") {
+            continue;
+        }
+        let bytes = std::fs::read(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let level = Level::from_ron(&bytes).map_err(|err| format!("{}: {err}", path.display()))?;
+        if let Err(errors) = level.validate() {
+            let errors = errors
+                .iter()
+                .map(|error| format!("\n  {error}"))
+                .collect::<String>();
+            return Err(format!("{}: level is invalid:{errors}", path.display()));
+        }
+        levels.push(level);
+    }
+    levels.sort_by(|a, b| (a.difficulty, &a.name).cmp(&(b.difficulty, &b.name)));
+    Ok(levels)
+}
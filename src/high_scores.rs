@@ -0,0 +1,108 @@
+use crate::storage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Number of entries kept per level.
+const TABLE_SIZE: usize = 10;
+
+pub struct HighScoresPlugin;
+
+impl Plugin for HighScoresPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(storage::load::<HighScores>("high_scores").unwrap_or_default());
+    }
+}
+
+#[derive(Debug, Default, Resource, Serialize, Deserialize)]
+pub struct HighScores {
+    /// The name used for the last entry, to prefill the name entry.
+    pub last_name: String,
+    /// Entries by level name, ordered from best to worst.
+    levels: HashMap<String, Vec<HighScoreEntry>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScoreEntry {
+    pub name: String,
+    pub score: u32,
+    pub kills: u32,
+    pub time_survived: f32,
+    pub home_health: f32,
+}
+
+impl HighScores {
+    pub fn entries(&self, level: &str) -> &[HighScoreEntry] {
+        self.levels.get(level).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn best_score(&self, level: &str) -> Option<u32> {
+        self.entries(level).first().map(|entry| entry.score)
+    }
+
+    pub fn best_home_health(&self, level: &str) -> Option<f32> {
+        self.entries(level)
+            .iter()
+            .map(|entry| entry.home_health)
+            .max_by(f32::total_cmp)
+    }
+
+    /// Whether a run with this score would make it into the table.
+    pub fn qualifies(&self, level: &str, score: u32) -> bool {
+        let entries = self.entries(level);
+        score > 0
+            && (entries.len() < TABLE_SIZE || entries.last().is_some_and(|last| score > last.score))
+    }
+
+    /// Inserts the entry, remembers its name and saves the table.
+    pub fn insert(&mut self, level: &str, entry: HighScoreEntry) {
+        self.add(level, entry);
+        storage::save("high_scores", self);
+    }
+
+    fn add(&mut self, level: &str, entry: HighScoreEntry) {
+        self.last_name.clone_from(&entry.name);
+
+        let entries = self.levels.entry(level.to_string()).or_default();
+        let index = entries.partition_point(|other| other.score >= entry.score);
+        entries.insert(index, entry);
+        entries.truncate(TABLE_SIZE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32, home_health: f32) -> HighScoreEntry {
+        HighScoreEntry {
+            name: format!("Player {score}"),
+            score,
+            kills: 0,
+            time_survived: 0.0,
+            home_health,
+        }
+    }
+
+    #[test]
+    fn table_is_sorted_and_truncated() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=TABLE_SIZE as u32 + 2 {
+            high_scores.add("Test", entry(score, score as f32 / 100.0));
+        }
+
+        let scores = high_scores
+            .entries("Test")
+            .iter()
+            .map(|entry| entry.score)
+            .collect::<Vec<_>>();
+        assert_eq!(scores, (3..=12).rev().collect::<Vec<_>>());
+        assert_eq!(high_scores.best_score("Test"), Some(12));
+        assert_eq!(high_scores.best_home_health("Test"), Some(0.12));
+        assert_eq!(high_scores.last_name, "Player 12");
+        assert!(!high_scores.qualifies("Test", 3));
+        assert!(high_scores.qualifies("Test", 4));
+        assert!(high_scores.qualifies("Other", 1));
+        assert!(!high_scores.qualifies("Other", 0));
+    }
+}
@@ -4,74 +4,217 @@
 // Feel free to delete this line.
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
+mod actions;
 mod assets;
+mod balance;
 mod camera;
+mod error_screen;
 mod full_screen;
 mod game;
+mod gamepad;
+mod headless;
+mod high_scores;
 mod main_menu;
+mod mixer;
 mod mute;
+mod options;
+mod settings;
 mod splash_screen;
+mod storage;
+mod touch;
 mod ui;
 
 #[cfg(feature = "dev")]
 mod dev;
 
 use bevy::{asset::AssetMetaCheck, prelude::*};
-use bevy_asset_loader::loading_state::{LoadingState, LoadingStateAppExt};
 
+pub use balance::{LevelReport, RunResult};
+pub use game::{
+    start_level, Appearance, Bot, Bullet, BulletHit, Collider, Enemy, ExplosionKind, GameMode,
+    GamePlugin, GameState, Health, Home, Level, LevelError, Mass, NextSeed, Planet,
+    PlanetDefinition, Player, PlayerDied, PlayerInput, PresentationPlugin, Replay, ReplayInput,
+    ReplayPlayback, RunStats, Seed, ShotFired, SimulationPlugin, SpaceShip, SpawnExplosion, Star,
+    StarDefinition, Velocity, SIMULATION_RATE,
+};
+pub use headless::{load_levels, Simulation};
+
+/// The game as shipped, starting with the splash screen.
 pub fn build_app() -> App {
-    let mut app = App::new();
-
-    app.add_plugins(
-        DefaultPlugins
-            .set(AssetPlugin {
-                // Wasm builds will check for meta files (that don't exist) if this isn't set.
-                // This causes errors and even panics in web builds on itch.
-                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
-                meta_check: AssetMetaCheck::Never,
+    AppBuilder::new().build()
+}
+
+/// Builds the app of the game, with options to embed it or start it differently.
+#[derive(Debug)]
+pub struct AppBuilder {
+    window: Window,
+    asset_path: String,
+    audio: bool,
+    fullscreen: Option<bool>,
+    muted: Option<bool>,
+    seed: Option<u64>,
+    mode: GameMode,
+    start: StartingState,
+}
+
+impl Default for AppBuilder {
+    fn default() -> Self {
+        Self {
+            window: Window {
+                fit_canvas_to_parent: true,
                 ..default()
-            })
-            .set(WindowPlugin {
-                primary_window: Some(Window {
-                    fit_canvas_to_parent: true,
+            },
+            asset_path: AssetPlugin::default().file_path,
+            audio: true,
+            fullscreen: None,
+            muted: None,
+            seed: None,
+            mode: GameMode::default(),
+            start: StartingState::default(),
+        }
+    }
+}
+
+impl AppBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The primary window.
+    pub fn window(mut self, window: Window) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// The folder with the levels and sounds, relative to the executable (or the crate during
+    /// `cargo run`). Defaults to `assets`.
+    pub fn asset_path(mut self, path: impl Into<String>) -> Self {
+        self.asset_path = path.into();
+        self
+    }
+
+    /// Skips loading the sounds and plays without audio.
+    pub fn without_audio(mut self) -> Self {
+        self.audio = false;
+        self
+    }
+
+    /// Overrides the window mode of the settings for this session.
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = Some(fullscreen);
+        self
+    }
+
+    /// Overrides whether audio is muted in the settings for this session.
+    pub fn muted(mut self, muted: bool) -> Self {
+        self.muted = Some(muted);
+        self
+    }
+
+    /// Starts every run with the same seed, instead of a random one.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// The mode selected at first.
+    pub fn mode(mut self, mode: GameMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Where to go once the assets are loaded.
+    pub fn start(mut self, start: StartingState) -> Self {
+        self.start = start;
+        self
+    }
+
+    pub fn build(self) -> App {
+        let mut app = App::new();
+
+        app.add_plugins(
+            DefaultPlugins
+                .set(AssetPlugin {
+                    file_path: self.asset_path,
+                    // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                    // This causes errors and even panics in web builds on itch.
+                    // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                    meta_check: AssetMetaCheck::Never,
+                    ..default()
+                })
+                .set(WindowPlugin {
+                    primary_window: Some(self.window),
                     ..default()
                 }),
-                ..default()
-            }),
-    )
-    .insert_resource(ClearColor(Color::srgb(0.02, 0.02, 0.02)));
-
-    app.init_state::<AppState>()
-        .enable_state_scoped_entities::<AppState>()
-        .init_state::<AssetsState>();
-
-    app.add_loading_state({
-        LoadingState::new(AssetsState::Loading)
-            .continue_to_state(AssetsState::Loaded)
-            .on_failure_continue_to_state(AssetsState::Error)
-    });
-
-    app.add_plugins((
-        assets::GameAssetsPlugin,
-        splash_screen::SplashScreenPlugin,
-        main_menu::MainMenuPlugin,
-        game::GamePlugin,
-        ui::UiPlugin,
-        full_screen::FullScreenPlugin,
-        mute::MutePlugin,
-        #[cfg(feature = "dev")]
-        dev::DevPlugin,
-    ));
-
-    app
+        )
+        .insert_resource(ClearColor(Color::srgb(0.02, 0.02, 0.02)));
+
+        app.init_state::<AppState>()
+            .enable_state_scoped_entities::<AppState>()
+            .init_state::<AssetsState>();
+
+        app.add_plugins((
+            settings::SettingsPlugin,
+            assets::GameAssetsPlugin,
+            splash_screen::SplashScreenPlugin,
+            main_menu::MainMenuPlugin,
+            error_screen::ErrorScreenPlugin,
+            game::GamePlugin,
+            ui::UiPlugin,
+            full_screen::FullScreenPlugin,
+            mute::MutePlugin,
+            high_scores::HighScoresPlugin,
+            options::OptionsPlugin,
+            mixer::MixerPlugin,
+            (
+                gamepad::GamepadPlugin,
+                touch::TouchPlugin,
+                actions::ActionsPlugin,
+            ),
+            #[cfg(feature = "dev")]
+            dev::DevPlugin,
+        ));
+
+        if !self.audio {
+            app.insert_resource(assets::NoAudio);
+        }
+        let window_mode = self.fullscreen.map(|fullscreen| match fullscreen {
+            true => settings::WindowModeSetting::Fullscreen,
+            false => settings::WindowModeSetting::Windowed,
+        });
+        settings::SessionOverrides::apply(app.world_mut(), window_mode, self.muted);
+
+        if let Some(seed) = self.seed {
+            app.insert_resource(NextSeed(Some(seed)));
+        }
+        app.insert_resource(self.mode).insert_resource(self.start);
+
+        app
+    }
+}
+
+/// Where the game goes after the splash screen, once the assets are loaded.
+#[derive(Debug, Clone, Default, Resource)]
+pub enum StartingState {
+    #[default]
+    MainMenu,
+    /// Starts the level with this name (ignoring case) or difficulty right away, in the current
+    /// [`GameMode`]. Leaving it returns to the main menu.
+    Level(String),
+    /// Watches the replay right away.
+    Replay(Replay),
 }
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
-enum AppState {
+pub enum AppState {
     #[default]
     SplashScreen,
     MainMenu,
     Game,
+    /// Transient state that (re-)enters [`AppState::Game`] with the current [`game::Level`].
+    /// Needed to restart, since setting the same state again does not run `OnExit`/`OnEnter`.
+    StartGame,
+    Error,
 }
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
@@ -1,7 +1,358 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use bevy::app::AppExit;
+use planet_guard::{
+    load_levels, AppBuilder, GameMode, GameState, Level, LevelReport, Replay, RunResult,
+    Simulation, StartingState, SIMULATION_RATE,
+};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "\
+Usage: planet_guard [OPTIONS]
+
+Options:
+  --level <NAME|DIFFICULTY>    Start this level right away, skipping the main menu
+  --mode <single|coop|versus>  The game mode [default: single]
+  --seed <NUMBER>              Start every run with this seed instead of a random one
+  --windowed                   Start in a window
+  --fullscreen                 Start in fullscreen
+  --mute                       Start muted
+  --replay <FILE>              Watch the replay stored in this RON file
+  --headless <SECONDS>         Play the level (or the replay) without a window for at most this
+                               long, without any input, and print the result
+  --bot                        Let bots play all players when running headless
+  --balance <RUNS>             Let bots play each level (or only --level) this many times, with
+                               the seeds counting up from --seed, and print how they did
+  --format <csv|json>          The output format of --balance [default: csv]
+  --assets <DIR>               The asset folder [default: assets]
+  -h, --help                   Print this help";
 
 fn main() -> AppExit {
-    planet_guard::build_app().run()
+    let args = Args::parse(std::env::args().skip(1));
+    // Only the game itself runs without printing anything.
+    if !matches!(&args, Ok(Some(args)) if args.headless.is_none() && args.balance.is_none()) {
+        attach_console();
+    }
+    let args = match args {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return AppExit::Success;
+        }
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            return AppExit::from_code(2);
+        }
+    };
+
+    let replay = match &args.replay {
+        Some(path) => match read_replay(path) {
+            Ok(replay) => Some(replay),
+            Err(err) => {
+                attach_console();
+                eprintln!("{err}");
+                return AppExit::error();
+            }
+        },
+        None => None,
+    };
+
+    if let Some(runs) = args.balance {
+        return match run_balance(&args, runs) {
+            Ok(()) => AppExit::Success,
+            Err(err) => {
+                eprintln!("{err}");
+                AppExit::error()
+            }
+        };
+    }
+
+    if let Some(seconds) = args.headless {
+        return match run_headless(&args, replay, seconds) {
+            Ok(()) => AppExit::Success,
+            Err(err) => {
+                eprintln!("{err}");
+                AppExit::error()
+            }
+        };
+    }
+
+    let mut builder = AppBuilder::new().asset_path(&args.assets).mode(args.mode);
+    if let Some(seed) = args.seed {
+        builder = builder.seed(seed);
+    }
+    if let Some(fullscreen) = args.fullscreen {
+        builder = builder.fullscreen(fullscreen);
+    }
+    if args.mute {
+        builder = builder.muted(true);
+    }
+    if let Some(replay) = replay {
+        builder = builder.start(StartingState::Replay(replay));
+    } else if let Some(level) = args.level {
+        builder = builder.start(StartingState::Level(level));
+    }
+    builder.build().run()
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Args {
+    level: Option<String>,
+    mode: GameMode,
+    seed: Option<u64>,
+    fullscreen: Option<bool>,
+    mute: bool,
+    replay: Option<PathBuf>,
+    headless: Option<f32>,
+    bot: bool,
+    balance: Option<u64>,
+    format: Option<Format>,
+    assets: String,
+}
+
+#[derive(Debug, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+impl Args {
+    /// The arguments, or `None` if the help was requested.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut parsed = Self {
+            assets: "assets".to_string(),
+            ..Self::default()
+        };
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("Missing value for {arg}"));
+            match arg.as_str() {
+                "--level" => parsed.level = Some(value()?),
+                "--mode" => {
+                    parsed.mode = match value()?.to_lowercase().as_str() {
+                        "single" => GameMode::Single,
+                        "coop" | "co-op" => GameMode::Coop,
+                        "versus" => GameMode::Versus,
+                        mode => return Err(format!("Unknown mode \"{mode}\"")),
+                    }
+                }
+                "--seed" => {
+                    let seed = value()?;
+                    parsed.seed = Some(seed.parse().map_err(|_| format!("Invalid seed {seed}"))?);
+                }
+                "--windowed" => parsed.fullscreen = Some(false),
+                "--fullscreen" => parsed.fullscreen = Some(true),
+                "--mute" => parsed.mute = true,
+                "--replay" => parsed.replay = Some(value()?.into()),
+                "--headless" => {
+                    let seconds = value()?;
+                    parsed.headless = Some(
+                        seconds
+                            .parse()
+                            .ok()
+                            .filter(|seconds: &f32| seconds.is_finite() && *seconds > 0.0)
+                            .ok_or(format!("Invalid number of seconds {seconds}"))?,
+                    );
+                }
+                "--bot" => parsed.bot = true,
+                "--balance" => {
+                    let runs = value()?;
+                    parsed.balance = Some(
+                        runs.parse()
+                            .map_err(|_| format!("Invalid number of runs {runs}"))?,
+                    );
+                }
+                "--format" => {
+                    parsed.format = Some(match value()?.to_lowercase().as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        format => return Err(format!("Unknown format \"{format}\"")),
+                    })
+                }
+                "--assets" => parsed.assets = value()?,
+                "-h" | "--help" => return Ok(None),
+                _ => return Err(format!("Unknown argument {arg}")),
+            }
+        }
+        if parsed.replay.is_some() && parsed.level.is_some() {
+            return Err("--level can not be combined with --replay".to_string());
+        }
+        if parsed.replay.is_some() && (parsed.bot || parsed.balance.is_some()) {
+            return Err("--bot and --balance can not be combined with --replay".to_string());
+        }
+        if parsed.bot && parsed.headless.is_none() && parsed.balance.is_none() {
+            return Err("--bot needs --headless or --balance".to_string());
+        }
+        if parsed.format.is_some() && parsed.balance.is_none() {
+            return Err("--format needs --balance".to_string());
+        }
+        Ok(Some(parsed))
+    }
+}
+
+/// Release builds on Windows have no console, so the output would be lost when started from a
+/// terminal. Attaches to the terminal's console instead, if there is one.
+#[cfg(all(windows, not(debug_assertions)))]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // SAFETY: No pointers are involved, and it fails harmlessly without a parent console or when
+    // already attached.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(all(windows, not(debug_assertions))))]
+fn attach_console() {}
+
+fn read_replay(path: &Path) -> Result<Replay, String> {
+    let replay =
+        std::fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    ron::from_str(&replay).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Plays the level or the replay without a window, as fast as possible.
+fn run_headless(args: &Args, replay: Option<Replay>, seconds: f32) -> Result<(), String> {
+    let levels = load_levels(Path::new(&args.assets).join("levels"))?;
+    let name = match (&replay, &args.level) {
+        (Some(replay), _) => Some(&replay.level),
+        (None, level) => level.as_ref(),
+    };
+    let level = match name {
+        Some(name) => find_level(&levels, name)?,
+        // The easiest level, like the first one in the main menu.
+        None => levels.first().ok_or("No levels found")?,
+    }
+    .clone();
+
+    let (mode, seed) = match &replay {
+        Some(replay) => (replay.mode, replay.seed),
+        None => (args.mode, args.seed.unwrap_or_else(rand::random)),
+    };
+    println!("Level:  {}", level.name);
+    println!("Mode:   {}", mode.label());
+    println!("Seed:   {seed}");
+
+    let mut simulation = match replay {
+        Some(replay) => Simulation::from_replay(level, replay),
+        None => Simulation::new(level, mode, seed),
+    };
+    if args.bot {
+        for player in 0..mode.players() {
+            simulation.add_bot(player);
+        }
+    }
+    let max_steps = (seconds as f64 * SIMULATION_RATE).round() as u64;
+    let steps = simulation.run_until_over(max_steps).unwrap_or(max_steps);
+
+    let result = match simulation.state() {
+        GameState::GameWon => "Won",
+        GameState::GameOver => "Lost",
+        GameState::Running | GameState::Paused => "Still running",
+    };
+    let stats = simulation.stats();
+    println!(
+        "Result: {result} after {:.1} s ({steps} steps)",
+        steps as f64 / SIMULATION_RATE
+    );
+    println!(
+        "Kills: {}, shots fired: {}, hits: {}, deaths: {}",
+        stats.kills, stats.shots_fired, stats.hits, stats.deaths
+    );
+    println!(
+        "Home health: {:.0}%, damage taken: {}",
+        stats.home_health * 100.0,
+        stats.home_damage_taken
+    );
+    println!("Score: {}", stats.score());
+    Ok(())
+}
+
+/// Lets bots play the levels many times, with the runs stopped after 10 minutes or `--headless`.
+fn run_balance(args: &Args, runs: u64) -> Result<(), String> {
+    let levels = load_levels(Path::new(&args.assets).join("levels"))?;
+    let levels = match &args.level {
+        Some(name) => vec![find_level(&levels, name)?],
+        None => levels.iter().collect(),
+    };
+    let first_seed = args.seed.unwrap_or(0);
+    let seeds = first_seed
+        ..first_seed
+            .checked_add(runs)
+            .ok_or("Too many runs for the seed to count up from --seed")?;
+    let max_steps = (args.headless.unwrap_or(600.0) as f64 * SIMULATION_RATE).round() as u64;
+
+    let reports = levels
+        .into_iter()
+        .map(|level| {
+            let results = RunResult::play_many(level, args.mode, seeds.clone(), max_steps);
+            LevelReport::new(&level.name, &results)
+        })
+        .collect::<Vec<_>>();
+
+    match args.format.as_ref().unwrap_or(&Format::Csv) {
+        Format::Csv => print!("{}", LevelReport::to_csv(&reports)),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&reports).map_err(|err| err.to_string())?
+        ),
+    }
+    Ok(())
+}
+
+fn find_level<'a>(levels: &'a [Level], name: &str) -> Result<&'a Level, String> {
+    levels
+        .iter()
+        .find(|level| level.matches(name))
+        .ok_or(format!("Level \"{name}\" not found"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<Args>, String> {
+        Args::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn parses_arguments() {
+        let args = parse(&[
+            "--level", "hard", "--mode", "Coop", "--seed", "42", "--mute",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(args.level.as_deref(), Some("hard"));
+        assert_eq!(args.mode, GameMode::Coop);
+        assert_eq!(args.seed, Some(42));
+        assert!(args.mute);
+        assert_eq!(args.assets, "assets");
+
+        assert_eq!(parse(&["--help"]), Ok(None));
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--headless", "-1"]).is_err());
+        assert!(parse(&["--headless", "0"]).is_err());
+        assert!(parse(&["--headless", "inf"]).is_err());
+        assert!(parse(&["--headless", "NaN"]).is_err());
+        assert!(parse(&["--level", "easy", "--replay", "replay.ron"]).is_err());
+        assert!(parse(&["--balance", "10", "--replay", "replay.ron"]).is_err());
+        assert!(parse(&["--balance", "10", "--format", "xml"]).is_err());
+        assert!(parse(&["--bot"]).is_err());
+        assert!(parse(&["--headless", "60", "--bot"]).unwrap().is_some());
+        assert!(parse(&["--format", "json"]).is_err());
+        assert!(parse(&["--balance", "10", "--format", "json"])
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn rejects_seeds_that_overflow() {
+        let args = parse(&["--balance", "10", "--seed", &u64::MAX.to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(run_balance(&args, 10).is_err());
+    }
 }
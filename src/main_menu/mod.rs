@@ -1,8 +1,11 @@
 use crate::{
-    assets::GameAssets,
+    assets::{GameAssets, LevelAssets},
     camera::GameCameraBundle,
-    game::{GameState, Level},
-    ui, AppState,
+    game::{self, GameMode, Level, NextSeed, Replays, StoredReplay},
+    high_scores::HighScores,
+    options::OptionsState,
+    ui::{self, TextInputSubmitted},
+    AppState,
 };
 use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
 
@@ -15,17 +18,34 @@ impl Plugin for MainMenuPlugin {
         app.add_systems(OnExit(AppState::MainMenu), cleanup);
 
         // Update
-        app.add_systems(Update, update.run_if(in_state(AppState::MainMenu)));
+        app.add_systems(
+            Update,
+            (update, update_labels, submit_seed).run_if(in_state(AppState::MainMenu)),
+        );
     }
 }
 
 #[derive(Debug, Component)]
 enum ButtonAction {
-    Easy,
-    Medium,
-    Hard,
+    Play(Handle<Level>),
+    Mode,
+    Seed,
+    /// Watches the stored replay with this number.
+    WatchReplay(u64),
+    Options,
 }
 
+/// Container of the seed button, replaced by a text input while entering a seed.
+#[derive(Debug, Component)]
+struct SeedEntry;
+
+#[derive(Debug, Component)]
+struct SeedInput;
+
+/// The best run of a level in the selected mode.
+#[derive(Debug, Component)]
+struct BestScore(String);
+
 fn update(
     mut commands: Commands,
     mut interaction_query: Query<
@@ -33,32 +53,153 @@ fn update(
         (Changed<Interaction>, With<Button>),
     >,
     mut next_state: ResMut<NextState<AppState>>,
-    mut next_state_game: ResMut<NextState<GameState>>,
+    mut next_state_options: ResMut<NextState<OptionsState>>,
+    mut mode: ResMut<GameMode>,
+    next_seed: Res<NextSeed>,
+    seed_entries: Query<Entity, With<SeedEntry>>,
+    levels: Res<Assets<Level>>,
+    replays: Res<Replays>,
 ) {
     for (interaction, action) in &mut interaction_query {
         if *interaction == Interaction::Pressed {
             match action {
-                ButtonAction::Easy => {
-                    commands.insert_resource(Level::easy());
-                    next_state.set(AppState::Game);
-                    next_state_game.set(GameState::Running);
+                ButtonAction::Play(handle) => {
+                    let Some(level) = levels.get(handle) else {
+                        continue;
+                    };
+                    game::start_level(&mut commands, level, &mut next_state);
                 }
-                ButtonAction::Medium => {
-                    commands.insert_resource(Level::medium());
-                    next_state.set(AppState::Game);
-                    next_state_game.set(GameState::Running);
+                ButtonAction::Mode => *mode = mode.next(),
+                ButtonAction::Seed => {
+                    let value = next_seed.0.map(|seed| seed.to_string()).unwrap_or_default();
+                    for entity in &seed_entries {
+                        commands
+                            .entity(entity)
+                            .despawn_descendants()
+                            .with_children(|parent| {
+                                ui::spawn_text_input_with(parent, value.clone(), 20, SeedInput);
+                            });
+                    }
                 }
-                ButtonAction::Hard => {
-                    commands.insert_resource(Level::hard());
-                    next_state.set(AppState::Game);
-                    next_state_game.set(GameState::Running);
+                ButtonAction::WatchReplay(number) => {
+                    let Some(replay) = replays.get(*number) else {
+                        continue;
+                    };
+                    let Some((_, level)) =
+                        levels.iter().find(|(_, level)| level.name == replay.level)
+                    else {
+                        warn!("Level \"{}\" of the replay not found", replay.level);
+                        continue;
+                    };
+                    game::start_replay(&mut commands, level, replay.clone(), &mut next_state);
                 }
+                ButtonAction::Options => next_state_options.set(OptionsState::Open),
             }
         }
     }
 }
 
-fn setup(mut commands: Commands, assets: Res<GameAssets>) {
+fn update_labels(
+    mode: Res<GameMode>,
+    high_scores: Res<HighScores>,
+    buttons: Query<(&ButtonAction, &Children)>,
+    mut best_scores: Query<(&BestScore, &mut Text)>,
+    mut texts: Query<&mut Text, Without<BestScore>>,
+) {
+    if !mode.is_changed() {
+        return;
+    }
+
+    for (action, children) in &buttons {
+        if let ButtonAction::Mode = action {
+            let mut texts = texts.iter_many_mut(children);
+            while let Some(mut text) = texts.fetch_next() {
+                text.sections[0].value = mode_label(*mode);
+            }
+        }
+    }
+    for (best_score, mut text) in &mut best_scores {
+        text.sections[0].value = best_score_label(&best_score.0, *mode, &high_scores);
+    }
+}
+
+/// An empty seed starts each run with a random seed.
+fn submit_seed(
+    mut commands: Commands,
+    mut events: EventReader<TextInputSubmitted>,
+    seed_inputs: Query<(), With<SeedInput>>,
+    seed_entries: Query<Entity, With<SeedEntry>>,
+    mut next_seed: ResMut<NextSeed>,
+) {
+    for event in events.read() {
+        if !seed_inputs.contains(event.entity) {
+            continue;
+        }
+
+        let value = event.value.trim();
+        next_seed.0 = match value.parse() {
+            Ok(seed) => Some(seed),
+            Err(_) if value.is_empty() => None,
+            Err(_) => {
+                warn!("Invalid seed \"{value}\", using random seeds");
+                None
+            }
+        };
+
+        for entity in &seed_entries {
+            commands
+                .entity(entity)
+                .despawn_descendants()
+                .with_children(|parent| {
+                    ui::spawn_button_with(parent, seed_label(&next_seed), ButtonAction::Seed);
+                });
+        }
+    }
+}
+
+fn seed_label(next_seed: &NextSeed) -> String {
+    match next_seed.0 {
+        Some(seed) => format!("Seed: {seed}"),
+        None => "Seed: Random".to_string(),
+    }
+}
+
+fn mode_label(mode: GameMode) -> String {
+    format!("Mode: {}", mode.label())
+}
+
+fn replay_label(stored: &StoredReplay) -> String {
+    format!(
+        "Replay {}: {} ({})",
+        stored.number,
+        stored.replay.level,
+        stored.replay.mode.label()
+    )
+}
+
+fn best_score_label(level: &str, mode: GameMode, high_scores: &HighScores) -> String {
+    let table = mode.high_score_table(level);
+    match (
+        high_scores.best_score(&table),
+        high_scores.best_home_health(&table),
+    ) {
+        (Some(score), Some(home_health)) => {
+            format!("Best: {score} | Home: {:.0}%", home_health * 100.0)
+        }
+        _ => String::new(),
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    high_scores: Res<HighScores>,
+    mode: Res<GameMode>,
+    next_seed: Res<NextSeed>,
+    replays: Res<Replays>,
+) {
     commands.spawn((GameCameraBundle::default(), StateScoped(AppState::MainMenu)));
     commands
         .spawn((
@@ -76,9 +217,34 @@ fn setup(mut commands: Commands, assets: Res<GameAssets>) {
             StateScoped(AppState::MainMenu),
         ))
         .with_children(|parent| {
-            ui::spawn_button_with(parent, "Easy", ButtonAction::Easy);
-            ui::spawn_button_with(parent, "Medium", ButtonAction::Medium);
-            ui::spawn_button_with(parent, "Hard", ButtonAction::Hard);
+            for (handle, level) in level_assets.sorted(&levels) {
+                ui::spawn_button_with(parent, &level.name, ButtonAction::Play(handle.clone()));
+                parent.spawn((
+                    TextBundle::from_section(
+                        best_score_label(&level.name, *mode, &high_scores),
+                        TextStyle {
+                            font_size: 16.0,
+                            color: Color::srgb(0.6, 0.6, 0.6),
+                            ..default()
+                        },
+                    ),
+                    BestScore(level.name.clone()),
+                ));
+            }
+            ui::spawn_button_with(parent, mode_label(*mode), ButtonAction::Mode);
+            parent
+                .spawn((NodeBundle::default(), SeedEntry))
+                .with_children(|parent| {
+                    ui::spawn_button_with(parent, seed_label(&next_seed), ButtonAction::Seed);
+                });
+            for stored in &replays.0 {
+                ui::spawn_button_with(
+                    parent,
+                    replay_label(stored),
+                    ButtonAction::WatchReplay(stored.number),
+                );
+            }
+            ui::spawn_button_with(parent, "Options", ButtonAction::Options);
         });
 
     commands.spawn((
@@ -118,4 +284,6 @@ fn setup(mut commands: Commands, assets: Res<GameAssets>) {
     ));
 }
 
-fn cleanup(mut _commands: Commands) {}
+fn cleanup(mut next_state_options: ResMut<NextState<OptionsState>>) {
+    next_state_options.set(OptionsState::Closed);
+}
@@ -0,0 +1,96 @@
+use crate::settings::Settings;
+use bevy::{audio::Volume, prelude::*, transform::TransformSystem};
+
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        // Bevy starts playing queued sounds after transform propagation.
+        app.add_systems(
+            PostUpdate,
+            init_volumes.before(TransformSystem::TransformPropagate),
+        );
+        app.add_systems(Update, update_volumes);
+    }
+}
+
+/// The mixer channel of a sound. Its volume is the master volume times the channel volume times
+/// the [`SoundVolume`] of the sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub enum AudioChannel {
+    Sfx,
+    /// Looping thruster sounds.
+    Engine,
+    /// For music, which the game does not have yet. Its volume is set and saved already.
+    Music,
+    Ui,
+}
+
+impl AudioChannel {
+    pub const ALL: [AudioChannel; 4] = [
+        AudioChannel::Sfx,
+        AudioChannel::Engine,
+        AudioChannel::Music,
+        AudioChannel::Ui,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AudioChannel::Sfx => "Effects",
+            AudioChannel::Engine => "Engine",
+            AudioChannel::Music => "Music",
+            AudioChannel::Ui => "Interface",
+        }
+    }
+}
+
+/// The volume of a sound relative to its channel. Can be changed while the sound is playing.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Deref, DerefMut)]
+pub struct SoundVolume(pub f32);
+
+#[derive(Bundle)]
+pub struct ChannelAudioBundle {
+    pub audio: AudioBundle,
+    pub channel: AudioChannel,
+    pub volume: SoundVolume,
+}
+
+impl ChannelAudioBundle {
+    /// The volume in `settings` is used as the [`SoundVolume`].
+    pub fn new(
+        channel: AudioChannel,
+        source: Handle<AudioSource>,
+        settings: PlaybackSettings,
+    ) -> Self {
+        Self {
+            volume: SoundVolume(*settings.volume),
+            audio: AudioBundle { source, settings },
+            channel,
+        }
+    }
+}
+
+/// Sets the volume of sounds that are about to start playing.
+fn init_volumes(
+    settings: Res<Settings>,
+    mut sounds: Query<(&AudioChannel, &SoundVolume, &mut PlaybackSettings), Without<AudioSink>>,
+) {
+    for (channel, volume, mut playback_settings) in &mut sounds {
+        let volume = Volume::new(settings.channel_volume(*channel) * **volume);
+        if *playback_settings.volume != *volume {
+            playback_settings.volume = volume;
+        }
+    }
+}
+
+/// Propagates volume changes to sounds that are already playing.
+fn update_volumes(
+    settings: Res<Settings>,
+    sounds: Query<(&AudioChannel, Ref<SoundVolume>, &AudioSink)>,
+) {
+    for (channel, volume, sink) in &sounds {
+        if settings.is_changed() || volume.is_changed() {
+            sink.set_volume(settings.channel_volume(*channel) * **volume);
+        }
+    }
+}
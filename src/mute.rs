@@ -1,3 +1,7 @@
+use crate::{
+    actions::{Action, ActionState},
+    settings::Settings,
+};
 use bevy::prelude::*;
 
 pub struct MutePlugin;
@@ -8,12 +12,8 @@ impl Plugin for MutePlugin {
     }
 }
 
-fn mute(mut global_volume: ResMut<GlobalVolume>, keyboard_input: Res<ButtonInput<KeyCode>>) {
-    if keyboard_input.just_pressed(KeyCode::KeyM) {
-        *global_volume = GlobalVolume::new(if *global_volume.volume < 1.0 {
-            1.0
-        } else {
-            0.0
-        });
+fn mute(mut settings: ResMut<Settings>, actions: Res<ActionState>) {
+    if actions.just_pressed(Action::Mute) {
+        settings.muted = !settings.muted;
     }
 }
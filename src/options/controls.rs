@@ -0,0 +1,180 @@
+use super::{column, overlay, title, OptionsState};
+use crate::{
+    actions::Action,
+    settings::{key_name, Bindings, Settings},
+    ui::{self, CapturesKeyboard},
+};
+use bevy::prelude::*;
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        // Setup
+        app.add_systems(OnEnter(OptionsState::Controls), setup);
+
+        // Update
+        app.add_systems(
+            Update,
+            (update_buttons, capture_key, update_labels)
+                .chain()
+                .run_if(in_state(OptionsState::Controls)),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+enum ButtonAction {
+    /// The player index and their action.
+    Rebind(usize, Action),
+    Reset,
+    Back,
+}
+
+/// The button waiting for a key to bind to the action of a player.
+#[derive(Debug, Component)]
+struct Rebinding(usize, Action);
+
+fn update_buttons(
+    mut commands: Commands,
+    interaction_query: Query<
+        (Entity, &Interaction, &ButtonAction),
+        (Changed<Interaction>, With<Button>),
+    >,
+    rebinding: Query<Entity, With<Rebinding>>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<OptionsState>>,
+) {
+    for (entity, interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            for entity in &rebinding {
+                commands
+                    .entity(entity)
+                    .remove::<(Rebinding, CapturesKeyboard)>();
+            }
+            match action {
+                ButtonAction::Rebind(player, action) => {
+                    commands
+                        .entity(entity)
+                        .insert((Rebinding(*player, *action), CapturesKeyboard));
+                }
+                ButtonAction::Reset => {
+                    settings.bindings = Bindings::default();
+                    settings.player_two_bindings = Bindings::player_two();
+                }
+                ButtonAction::Back => next_state.set(OptionsState::Open),
+            }
+        }
+    }
+}
+
+/// Binds the next pressed key, ESC cancels.
+fn capture_key(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    rebinding: Query<(Entity, &Rebinding)>,
+    mut settings: ResMut<Settings>,
+) {
+    let Ok((entity, rebinding)) = rebinding.get_single() else {
+        return;
+    };
+    let Some(key) = keyboard_input.get_just_pressed().next() else {
+        return;
+    };
+
+    if *key != KeyCode::Escape {
+        settings.rebind(rebinding.0, rebinding.1, *key);
+    }
+    commands
+        .entity(entity)
+        .remove::<(Rebinding, CapturesKeyboard)>();
+}
+
+fn update_labels(
+    settings: Res<Settings>,
+    buttons: Query<(&ButtonAction, Has<Rebinding>, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (action, rebinding, children) in &buttons {
+        let ButtonAction::Rebind(player, action) = action else {
+            continue;
+        };
+        let label = if rebinding {
+            format!("{}: ...", action_label(*player, *action))
+        } else {
+            label(*player, *action, &settings)
+        };
+
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            if text.sections[0].value != label {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+fn label(player: usize, action: Action, settings: &Settings) -> String {
+    let keys = settings
+        .bindings(player)
+        .get(action)
+        .0
+        .iter()
+        .copied()
+        .map(key_name);
+    format!(
+        "{}: {}",
+        action_label(player, action),
+        keys.collect::<Vec<_>>().join(" ")
+    )
+}
+
+fn action_label(player: usize, action: Action) -> String {
+    match player {
+        0 => action.label().to_string(),
+        _ => format!("P2 {}", action.label()),
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn(overlay(OptionsState::Controls))
+        .with_children(|parent| {
+            parent.spawn(title("CONTROLS"));
+            parent.spawn(NodeBundle::default()).with_children(|parent| {
+                let columns = Action::ALL
+                    .chunks(5)
+                    .map(|actions| (0, actions))
+                    .chain([(1, &Action::SHIP[..])]);
+                for (player, actions) in columns {
+                    parent.spawn(column()).with_children(|parent| {
+                        for action in actions {
+                            ui::spawn_button_with(
+                                parent,
+                                label(player, *action, &settings),
+                                ButtonAction::Rebind(player, *action),
+                            );
+                        }
+                    });
+                }
+            });
+            parent.spawn(
+                TextBundle::from_section(
+                    "Click an action and press a key to bind it, ESC to cancel.\n\
+                     Gamepad: left stick to steer, RT throttle, LT brake, A fire, \
+                     Start pause, hold Select to quit.\n\
+                     In co-op, the P2 keys and the second gamepad control the second player.",
+                    TextStyle {
+                        font_size: 16.0,
+                        color: Color::srgb(0.6, 0.6, 0.6),
+                        ..default()
+                    },
+                )
+                .with_text_justify(JustifyText::Center),
+            );
+            parent.spawn(NodeBundle::default()).with_children(|parent| {
+                ui::spawn_button_with(parent, "Reset", ButtonAction::Reset);
+                ui::spawn_button_with(parent, "Back", ButtonAction::Back);
+            });
+        });
+}
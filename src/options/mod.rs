@@ -0,0 +1,208 @@
+use crate::{
+    mixer::AudioChannel,
+    settings::Settings,
+    ui::{self, Slider},
+};
+use bevy::{prelude::*, ui::FocusPolicy};
+
+mod controls;
+
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<OptionsState>()
+            .enable_state_scoped_entities::<OptionsState>();
+
+        app.add_plugins(controls::ControlsPlugin);
+
+        // Setup
+        app.add_systems(OnEnter(OptionsState::Open), setup);
+
+        // Update
+        app.add_systems(
+            Update,
+            (update_buttons, update_sliders, update_labels).run_if(in_state(OptionsState::Open)),
+        );
+    }
+}
+
+/// The options screen is an overlay, so it can be opened from the main menu and the pause menu.
+/// Screens opening it are responsible for closing it again when they are left.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
+pub enum OptionsState {
+    #[default]
+    Closed,
+    Open,
+    /// Key bindings, opened from the options.
+    Controls,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+enum ButtonAction {
+    WindowMode,
+    ControlScheme,
+    Bloom,
+    ScreenEffects,
+    Controls,
+    Back,
+}
+
+impl ButtonAction {
+    fn label(&self, settings: &Settings) -> String {
+        let on_off = |value| if value { "On" } else { "Off" };
+        match self {
+            ButtonAction::WindowMode => format!("Window: {}", settings.window_mode.label()),
+            ButtonAction::ControlScheme => format!("Steer: {}", settings.control_scheme.label()),
+            ButtonAction::Bloom => format!("Bloom: {}", on_off(settings.bloom)),
+            ButtonAction::ScreenEffects => {
+                format!("Screen Effects: {}", on_off(settings.screen_effects))
+            }
+            ButtonAction::Controls => "Controls".to_string(),
+            ButtonAction::Back => "Back".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Component)]
+enum VolumeSlider {
+    Master,
+    Channel(AudioChannel),
+}
+
+fn update_buttons(
+    interaction_query: Query<(&Interaction, &ButtonAction), (Changed<Interaction>, With<Button>)>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<OptionsState>>,
+) {
+    for (interaction, action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match action {
+                ButtonAction::WindowMode => settings.window_mode = settings.window_mode.toggled(),
+                ButtonAction::ControlScheme => {
+                    settings.control_scheme = settings.control_scheme.toggled();
+                }
+                ButtonAction::Bloom => settings.bloom = !settings.bloom,
+                ButtonAction::ScreenEffects => settings.screen_effects = !settings.screen_effects,
+                ButtonAction::Controls => next_state.set(OptionsState::Controls),
+                ButtonAction::Back => next_state.set(OptionsState::Closed),
+            }
+        }
+    }
+}
+
+fn update_sliders(
+    sliders: Query<(&Slider, &VolumeSlider), Changed<Slider>>,
+    mut settings: ResMut<Settings>,
+) {
+    for (slider, action) in &sliders {
+        // Only marks the settings as changed (and saves them) if the value differs.
+        let mut volume = settings.reborrow().map_unchanged(|settings| match action {
+            VolumeSlider::Master => &mut settings.master_volume,
+            VolumeSlider::Channel(channel) => settings.channel_volume_mut(*channel),
+        });
+        volume.set_if_neq(slider.value);
+    }
+}
+
+/// Keeps the button labels in sync, since settings can also change through shortcuts (e.g. F11).
+fn update_labels(
+    settings: Res<Settings>,
+    buttons: Query<(&ButtonAction, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    for (action, children) in &buttons {
+        let mut texts = texts.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.sections[0].value = action.label(&settings);
+        }
+    }
+}
+
+fn setup(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn(overlay(OptionsState::Open))
+        .with_children(|parent| {
+            parent.spawn(title("OPTIONS"));
+            parent.spawn(NodeBundle::default()).with_children(|parent| {
+                parent.spawn(column()).with_children(|parent| {
+                    ui::spawn_slider_with(
+                        parent,
+                        "Master",
+                        settings.master_volume,
+                        VolumeSlider::Master,
+                    );
+                    for channel in AudioChannel::ALL {
+                        ui::spawn_slider_with(
+                            parent,
+                            channel.label(),
+                            settings.channel_volume_setting(channel),
+                            VolumeSlider::Channel(channel),
+                        );
+                    }
+                });
+                parent.spawn(column()).with_children(|parent| {
+                    for action in [
+                        ButtonAction::WindowMode,
+                        ButtonAction::ControlScheme,
+                        ButtonAction::Bloom,
+                        ButtonAction::ScreenEffects,
+                        ButtonAction::Controls,
+                        ButtonAction::Back,
+                    ] {
+                        ui::spawn_button_with(parent, action.label(&settings), action);
+                    }
+                });
+            });
+        });
+}
+
+fn overlay(state: OptionsState) -> impl Bundle {
+    (
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.9)),
+            // Drawn above and blocks the screen that opened the options.
+            focus_policy: FocusPolicy::Block,
+            z_index: ZIndex::Global(1),
+            ..default()
+        },
+        StateScoped(state),
+    )
+}
+
+fn title(text: &str) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font_size: 80.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    )
+    .with_style(Style {
+        margin: UiRect::bottom(Val::Px(20.0)),
+        ..default()
+    })
+}
+
+fn column() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            ..default()
+        },
+        ..default()
+    }
+}
@@ -0,0 +1,484 @@
+use crate::{actions::Action, mixer::AudioChannel, storage};
+use bevy::{
+    core_pipeline::bloom::BloomSettings,
+    prelude::*,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // Loaded when building the app, so the settings are available before the splash screen.
+        app.insert_resource(storage::load::<Settings>("settings").unwrap_or_default());
+
+        app.init_resource::<SaveTimer>()
+            .init_resource::<SessionOverrides>();
+
+        app.add_systems(
+            Update,
+            apply_window_mode.run_if(resource_changed::<Settings>),
+        );
+        app.add_systems(Update, (apply_bloom, save_settings));
+        app.add_systems(Last, save_settings_on_exit.run_if(on_event::<AppExit>()));
+    }
+}
+
+/// User settings, saved shortly after they change.
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub master_volume: f32,
+    pub sfx_volume: f32,
+    pub engine_volume: f32,
+    pub music_volume: f32,
+    pub ui_volume: f32,
+    pub muted: bool,
+    pub window_mode: WindowModeSetting,
+    pub control_scheme: ControlScheme,
+    pub bloom: bool,
+    /// Visual effects like explosion particles.
+    pub screen_effects: bool,
+    pub bindings: Bindings,
+    /// The ship controls of the second player in co-op.
+    pub player_two_bindings: Bindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            sfx_volume: 1.0,
+            engine_volume: 1.0,
+            music_volume: 1.0,
+            ui_volume: 1.0,
+            muted: false,
+            window_mode: WindowModeSetting::Windowed,
+            control_scheme: ControlScheme::Keyboard,
+            bloom: true,
+            screen_effects: true,
+            bindings: Bindings::default(),
+            player_two_bindings: Bindings::player_two(),
+        }
+    }
+}
+
+impl Settings {
+    /// The key bindings of the player with the given index.
+    pub fn bindings(&self, player: usize) -> &Bindings {
+        match player {
+            0 => &self.bindings,
+            _ => &self.player_two_bindings,
+        }
+    }
+
+    /// Binds only `key` to the action of the player, and removes it from all other actions of
+    /// both players.
+    pub fn rebind(&mut self, player: usize, action: Action, key: KeyCode) {
+        let (bindings, other) = match player {
+            0 => (&mut self.bindings, &mut self.player_two_bindings),
+            _ => (&mut self.player_two_bindings, &mut self.bindings),
+        };
+        other.unbind(key);
+        bindings.rebind(action, key);
+    }
+
+    /// The master volume, taking mute into account.
+    pub fn master_volume(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.master_volume
+        }
+    }
+
+    pub fn channel_volume_mut(&mut self, channel: AudioChannel) -> &mut f32 {
+        match channel {
+            AudioChannel::Sfx => &mut self.sfx_volume,
+            AudioChannel::Engine => &mut self.engine_volume,
+            AudioChannel::Music => &mut self.music_volume,
+            AudioChannel::Ui => &mut self.ui_volume,
+        }
+    }
+
+    /// The volume of the channel as set by the user, see [`Settings::channel_volume`].
+    pub fn channel_volume_setting(&self, channel: AudioChannel) -> f32 {
+        match channel {
+            AudioChannel::Sfx => self.sfx_volume,
+            AudioChannel::Engine => self.engine_volume,
+            AudioChannel::Music => self.music_volume,
+            AudioChannel::Ui => self.ui_volume,
+        }
+    }
+
+    /// The effective volume of the channel, including the master volume.
+    pub fn channel_volume(&self, channel: AudioChannel) -> f32 {
+        self.master_volume() * self.channel_volume_setting(channel)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WindowModeSetting {
+    Windowed,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Windowed => Self::Fullscreen,
+            Self::Fullscreen => Self::Windowed,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Windowed => "Windowed",
+            Self::Fullscreen => "Fullscreen",
+        }
+    }
+}
+
+/// How the player ship is steered. The key bindings work with both, steering keys take over
+/// from the mouse while held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ControlScheme {
+    Keyboard,
+    /// The ship turns toward the mouse cursor, left click fires and right click thrusts.
+    MouseAim,
+}
+
+impl ControlScheme {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Keyboard => Self::MouseAim,
+            Self::MouseAim => Self::Keyboard,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Keyboard => "Keyboard",
+            Self::MouseAim => "Mouse Aim",
+        }
+    }
+}
+
+impl From<WindowModeSetting> for WindowMode {
+    fn from(mode: WindowModeSetting) -> Self {
+        match mode {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Fullscreen => WindowMode::BorderlessFullscreen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    pub throttle: Binding,
+    pub brake: Binding,
+    pub steer_left: Binding,
+    pub steer_right: Binding,
+    pub fire: Binding,
+    pub pause: Binding,
+    pub quit: Binding,
+    pub mute: Binding,
+    pub fullscreen: Binding,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            throttle: Binding(vec![KeyCode::KeyW, KeyCode::ArrowUp]),
+            brake: Binding(vec![KeyCode::KeyS, KeyCode::ArrowDown]),
+            steer_left: Binding(vec![KeyCode::KeyA, KeyCode::ArrowLeft]),
+            steer_right: Binding(vec![KeyCode::KeyD, KeyCode::ArrowRight]),
+            fire: Binding(vec![KeyCode::Space]),
+            pause: Binding(vec![KeyCode::KeyP]),
+            quit: Binding(vec![KeyCode::Escape, KeyCode::Backspace]),
+            mute: Binding(vec![KeyCode::KeyM]),
+            fullscreen: Binding(vec![KeyCode::F11]),
+        }
+    }
+}
+
+impl Bindings {
+    /// Only the ship controls, on the right side of the keyboard.
+    pub fn player_two() -> Self {
+        Self {
+            throttle: Binding(vec![KeyCode::KeyI]),
+            brake: Binding(vec![KeyCode::KeyK]),
+            steer_left: Binding(vec![KeyCode::KeyJ]),
+            steer_right: Binding(vec![KeyCode::KeyL]),
+            fire: Binding(vec![KeyCode::Enter]),
+            pause: Binding(vec![]),
+            quit: Binding(vec![]),
+            mute: Binding(vec![]),
+            fullscreen: Binding(vec![]),
+        }
+    }
+
+    pub fn get(&self, action: Action) -> &Binding {
+        match action {
+            Action::Throttle => &self.throttle,
+            Action::Brake => &self.brake,
+            Action::SteerLeft => &self.steer_left,
+            Action::SteerRight => &self.steer_right,
+            Action::Fire => &self.fire,
+            Action::Pause => &self.pause,
+            Action::Quit => &self.quit,
+            Action::Mute => &self.mute,
+            Action::Fullscreen => &self.fullscreen,
+        }
+    }
+
+    pub fn get_mut(&mut self, action: Action) -> &mut Binding {
+        match action {
+            Action::Throttle => &mut self.throttle,
+            Action::Brake => &mut self.brake,
+            Action::SteerLeft => &mut self.steer_left,
+            Action::SteerRight => &mut self.steer_right,
+            Action::Fire => &mut self.fire,
+            Action::Pause => &mut self.pause,
+            Action::Quit => &mut self.quit,
+            Action::Mute => &mut self.mute,
+            Action::Fullscreen => &mut self.fullscreen,
+        }
+    }
+
+    /// Binds only `key` to the action and removes it from all other actions.
+    pub fn rebind(&mut self, action: Action, key: KeyCode) {
+        self.unbind(key);
+        self.get_mut(action).0 = vec![key];
+    }
+
+    /// Removes `key` from all actions.
+    pub fn unbind(&mut self, key: KeyCode) {
+        for action in Action::ALL {
+            self.get_mut(action).0.retain(|other_key| *other_key != key);
+        }
+    }
+}
+
+/// The keys bound to a single action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Binding(pub Vec<KeyCode>);
+
+impl Binding {
+    /// The first bound key that is pressed.
+    pub fn pressed_key(&self, input: &ButtonInput<KeyCode>) -> Option<KeyCode> {
+        self.0.iter().copied().find(|key| input.pressed(*key))
+    }
+}
+
+/// A short, human readable name of the key, e.g. `ESC` or `W`.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Escape => "ESC".to_string(),
+        KeyCode::ArrowUp => "UP".to_string(),
+        KeyCode::ArrowDown => "DOWN".to_string(),
+        KeyCode::ArrowLeft => "LEFT".to_string(),
+        KeyCode::ArrowRight => "RIGHT".to_string(),
+        _ => {
+            let name = format!("{key:?}");
+            let name = name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+                .unwrap_or(&name);
+            name.to_uppercase()
+        }
+    }
+}
+
+fn apply_window_mode(
+    settings: Res<Settings>,
+    mut primary_window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if let Ok(mut window) = primary_window.get_single_mut() {
+        let mode = WindowMode::from(settings.window_mode);
+        if window.mode != mode {
+            window.mode = mode;
+        }
+    }
+}
+
+/// Runs every frame, so cameras spawned later also follow the setting.
+fn apply_bloom(settings: Res<Settings>, mut cameras: Query<&mut BloomSettings>) {
+    let intensity = if settings.bloom {
+        BloomSettings::default().intensity
+    } else {
+        0.0
+    };
+    for mut bloom in &mut cameras {
+        if bloom.intensity != intensity {
+            bloom.intensity = intensity;
+        }
+    }
+}
+
+/// Settings overridden for this session only, e.g. from the command line. The saved values are
+/// saved instead, until the setting is changed during the session.
+#[derive(Debug, Default, Resource)]
+pub struct SessionOverrides {
+    window_mode: Option<Overridden<WindowModeSetting>>,
+    muted: Option<Overridden<bool>>,
+}
+
+#[derive(Debug)]
+struct Overridden<T> {
+    saved: T,
+    session: T,
+}
+
+impl<T: Copy + PartialEq> Overridden<T> {
+    fn new(setting: &mut T, session: T) -> Self {
+        let saved = std::mem::replace(setting, session);
+        Self { saved, session }
+    }
+
+    /// The value to save, or `None` once the setting was changed during the session.
+    fn saved(&self, setting: T) -> Option<T> {
+        (setting == self.session).then_some(self.saved)
+    }
+}
+
+impl SessionOverrides {
+    /// Overrides the loaded settings, which requires the [`SettingsPlugin`].
+    pub fn apply(world: &mut World, window_mode: Option<WindowModeSetting>, muted: Option<bool>) {
+        world.resource_scope(|world, mut overrides: Mut<SessionOverrides>| {
+            let mut settings = world.resource_mut::<Settings>();
+            if let Some(window_mode) = window_mode {
+                overrides.window_mode =
+                    Some(Overridden::new(&mut settings.window_mode, window_mode));
+            }
+            if let Some(muted) = muted {
+                overrides.muted = Some(Overridden::new(&mut settings.muted, muted));
+            }
+        });
+    }
+
+    /// The settings to save, with the saved values of all settings that are still overridden.
+    fn saved(&mut self, settings: &Settings) -> Settings {
+        let mut saved = settings.clone();
+        match self
+            .window_mode
+            .as_ref()
+            .map(|overridden| overridden.saved(settings.window_mode))
+        {
+            Some(Some(window_mode)) => saved.window_mode = window_mode,
+            Some(None) => self.window_mode = None,
+            None => (),
+        }
+        match self
+            .muted
+            .as_ref()
+            .map(|overridden| overridden.saved(settings.muted))
+        {
+            Some(Some(muted)) => saved.muted = muted,
+            Some(None) => self.muted = None,
+            None => (),
+        }
+        saved
+    }
+}
+
+/// Seconds the settings have to stay unchanged before they are saved, so e.g. dragging a volume
+/// slider does not write them every frame.
+const SAVE_DELAY: f32 = 0.5;
+
+/// Counts down to saving the changed settings, in real time since the options can be opened
+/// while the game is paused.
+#[derive(Debug, Default, Resource)]
+struct SaveTimer(Option<Timer>);
+
+fn save_settings(
+    settings: Res<Settings>,
+    time: Res<Time<Real>>,
+    mut save_timer: ResMut<SaveTimer>,
+    mut overrides: ResMut<SessionOverrides>,
+) {
+    // Nothing to save right after loading.
+    if settings.is_changed() && !settings.is_added() {
+        save_timer.0 = Some(Timer::from_seconds(SAVE_DELAY, TimerMode::Once));
+    }
+
+    if let Some(timer) = &mut save_timer.0 {
+        if timer.tick(time.delta()).finished() {
+            storage::save("settings", &overrides.saved(&settings));
+            save_timer.0 = None;
+        }
+    }
+}
+
+/// Saves settings that changed right before quitting.
+fn save_settings_on_exit(
+    settings: Res<Settings>,
+    mut save_timer: ResMut<SaveTimer>,
+    mut overrides: ResMut<SessionOverrides>,
+) {
+    if save_timer.0.take().is_some() {
+        storage::save("settings", &overrides.saved(&settings));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let settings: Settings =
+            ron::from_str("(sfx_volume: 0.5, bindings: (fire: [Enter]))").unwrap();
+        assert_eq!(settings.master_volume, 1.0);
+        assert_eq!(settings.sfx_volume, 0.5);
+        assert_eq!(settings.bindings.fire, Binding(vec![KeyCode::Enter]));
+        assert_eq!(settings.bindings.pause, Bindings::default().pause);
+    }
+
+    #[test]
+    fn rebinding_removes_key_from_other_actions() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Fire, KeyCode::KeyW);
+        assert_eq!(bindings.fire, Binding(vec![KeyCode::KeyW]));
+        assert_eq!(bindings.throttle, Binding(vec![KeyCode::ArrowUp]));
+    }
+
+    #[test]
+    fn rebinding_removes_key_from_other_player() {
+        let mut settings = Settings::default();
+        settings.rebind(1, Action::Fire, KeyCode::Space);
+        assert_eq!(
+            settings.player_two_bindings.fire,
+            Binding(vec![KeyCode::Space])
+        );
+        assert_eq!(settings.bindings.fire, Binding(vec![]));
+    }
+
+    #[test]
+    fn session_overrides_are_not_saved() {
+        let mut settings = Settings::default();
+        let mut overrides = SessionOverrides {
+            muted: Some(Overridden::new(&mut settings.muted, true)),
+            ..default()
+        };
+        assert!(settings.muted);
+        assert!(!overrides.saved(&settings).muted);
+
+        // Changed during the session, so saved from now on.
+        settings.muted = false;
+        assert!(!overrides.saved(&settings).muted);
+        settings.muted = true;
+        assert!(overrides.saved(&settings).muted);
+    }
+
+    #[test]
+    fn key_names() {
+        assert_eq!(key_name(KeyCode::KeyW), "W");
+        assert_eq!(key_name(KeyCode::Digit1), "1");
+        assert_eq!(key_name(KeyCode::Escape), "ESC");
+        assert_eq!(key_name(KeyCode::Backspace), "BACKSPACE");
+    }
+}
@@ -1,4 +1,9 @@
-use crate::{AppState, AssetsState};
+use crate::{
+    assets::{FailedAssets, LevelAssets},
+    error_screen::{ErrorAction, ErrorScreen},
+    game::{start_level, start_replay, Level},
+    AppState, AssetsState, StartingState,
+};
 use bevy::prelude::*;
 
 pub struct SplashScreenPlugin;
@@ -34,24 +39,84 @@ impl Default for SplashScreen {
 }
 
 fn splash_screen(
+    mut commands: Commands,
     time: Res<Time>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
     assets_state: Res<State<AssetsState>>,
+    failed_assets: Res<FailedAssets>,
+    level_assets: Option<Res<LevelAssets>>,
+    levels: Res<Assets<Level>>,
+    starting_state: Option<Res<StartingState>>,
     mut splash_screen: ResMut<SplashScreen>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     splash_screen.timer.tick(time.delta());
-    splash_screen.clicked |=
-        keyboard_input.just_pressed(KeyCode::Space) || mouse_input.just_pressed(MouseButton::Left);
+    splash_screen.clicked |= keyboard_input.just_pressed(KeyCode::Space)
+        || mouse_input.just_pressed(MouseButton::Left)
+        || touches.any_just_pressed();
 
-    // TODO: Handle AssetsState::Error
+    if **assets_state == AssetsState::Error {
+        // Only audio can be missing if the levels loaded fine.
+        let actions = match level_assets {
+            Some(_) => vec![ErrorAction::RetryLoading, ErrorAction::ContinueWithoutAudio],
+            None => vec![ErrorAction::RetryLoading],
+        };
+        commands.insert_resource(ErrorScreen {
+            title: "Failed to load assets".to_string(),
+            details: failed_assets.0.clone(),
+            actions,
+        });
+        next_state.set(AppState::Error);
+        return;
+    }
 
     if **assets_state == AssetsState::Loaded
         && (splash_screen.timer.finished() || splash_screen.clicked)
     {
-        next_state.set(AppState::MainMenu);
+        // Only the first time, e.g. not after retrying to load the assets from the main menu.
+        commands.remove_resource::<StartingState>();
+        let level_assets = level_assets.expect("levels are loaded");
+        let levels = level_assets
+            .sorted(&levels)
+            .into_iter()
+            .map(|(_, level)| level)
+            .collect::<Vec<_>>();
+        match starting_state.as_deref() {
+            None | Some(StartingState::MainMenu) => next_state.set(AppState::MainMenu),
+            Some(StartingState::Level(name)) => match find_level(&mut commands, &levels, name) {
+                Some(level) => {
+                    start_level(&mut commands, level, &mut next_state);
+                }
+                None => next_state.set(AppState::Error),
+            },
+            Some(StartingState::Replay(replay)) => {
+                match find_level(&mut commands, &levels, &replay.level) {
+                    Some(level) => {
+                        start_replay(&mut commands, level, replay.clone(), &mut next_state);
+                    }
+                    None => next_state.set(AppState::Error),
+                }
+            }
+        }
+    }
+}
+
+/// The level with the name or difficulty, or `None` after setting up the error screen.
+fn find_level<'a>(commands: &mut Commands, levels: &[&'a Level], name: &str) -> Option<&'a Level> {
+    let level = levels.iter().find(|level| level.matches(name)).copied();
+    if level.is_none() {
+        commands.insert_resource(ErrorScreen {
+            title: format!("Level \"{name}\" not found"),
+            details: levels
+                .iter()
+                .map(|level| format!("{} (difficulty {})", level.name, level.difficulty))
+                .collect(),
+            actions: vec![ErrorAction::MainMenu],
+        });
     }
+    level
 }
 
 fn setup(mut commands: Commands) {
@@ -0,0 +1,129 @@
+//! Persistent key-value storage: RON files in the platform data directory on native,
+//! `localStorage` on the web.
+
+use bevy::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Loads the value stored under `key`. Missing or unreadable values are treated as absent.
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let value = read(key)?;
+    match ron::from_str(&value) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("Failed to parse stored {key}: {err}");
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, default()) {
+        Ok(value) => write(key, &value),
+        Err(err) => warn!("Failed to serialize {key}: {err}"),
+    }
+}
+
+/// The names of the values stored under `dir`, in no particular order. The key of each is
+/// `{dir}/{name}`.
+pub fn names(dir: &str) -> Vec<String> {
+    list(dir)
+}
+
+pub fn remove(key: &str) {
+    delete(key);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn root() -> Option<std::path::PathBuf> {
+    Some(dirs::data_dir()?.join("planet_guard"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn path(key: &str) -> Option<std::path::PathBuf> {
+    Some(root()?.join(format!("{key}.ron")))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)?).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(key: &str, value: &str) {
+    let Some(path) = path(key) else {
+        warn!("No data directory to store {key} in");
+        return;
+    };
+    let result =
+        std::fs::create_dir_all(path.parent().unwrap()).and_then(|()| std::fs::write(&path, value));
+    if let Err(err) = result {
+        warn!("Failed to write {}: {err}", path.display());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn list(dir: &str) -> Vec<String> {
+    let Some(entries) = root().and_then(|root| std::fs::read_dir(root.join(dir)).ok()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "ron" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn delete(key: &str) {
+    let Some(path) = path(key) else {
+        return;
+    };
+    if let Err(err) = std::fs::remove_file(&path) {
+        warn!("Failed to remove {}: {err}", path.display());
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(key: &str) -> Option<String> {
+    local_storage()?
+        .get_item(&format!("planet_guard.{key}"))
+        .ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(key: &str, value: &str) {
+    let result =
+        local_storage().map(|storage| storage.set_item(&format!("planet_guard.{key}"), value));
+    if !matches!(result, Some(Ok(()))) {
+        warn!("Failed to write {key} to local storage");
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn list(dir: &str) -> Vec<String> {
+    let Some(storage) = local_storage() else {
+        return Vec::new();
+    };
+    let prefix = format!("planet_guard.{dir}/");
+    (0..storage.length().unwrap_or(0))
+        .filter_map(|index| storage.key(index).ok()?)
+        .filter_map(|key| Some(key.strip_prefix(&prefix)?.to_string()))
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn delete(key: &str) {
+    let result = local_storage().map(|storage| storage.remove_item(&format!("planet_guard.{key}")));
+    if !matches!(result, Some(Ok(()))) {
+        warn!("Failed to remove {key} from local storage");
+    }
+}
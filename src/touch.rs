@@ -0,0 +1,271 @@
+use crate::{actions::UpdateActions, game::GameState, AppState};
+use bevy::{input::InputSystem, prelude::*};
+
+pub struct TouchPlugin;
+
+impl Plugin for TouchPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>();
+
+        // Setup
+        app.add_systems(OnEnter(AppState::Game), setup);
+
+        // Update
+        app.add_systems(
+            PreUpdate,
+            update_touch_controls
+                .after(InputSystem)
+                .before(UpdateActions),
+        );
+        app.add_systems(Update, (update_ui, pause).run_if(in_state(AppState::Game)));
+    }
+}
+
+/// On-screen controls, shown after the screen is touched and hidden again when a key is pressed.
+#[derive(Debug, Default, Resource)]
+pub struct TouchControls {
+    visible: bool,
+    /// The joystick deflection, with y pointing up.
+    joystick: Vec2,
+    pub fire: bool,
+    pub brake: bool,
+}
+
+impl TouchControls {
+    /// From -1 (full left) to 1 (full right).
+    pub fn steer(&self) -> f32 {
+        self.joystick.x
+    }
+
+    /// From 0 to 1, by pushing the joystick up.
+    pub fn throttle(&self) -> f32 {
+        self.joystick.y.max(0.0)
+    }
+}
+
+#[derive(Debug, Component)]
+struct TouchUi;
+
+#[derive(Debug, Component)]
+struct Joystick;
+
+#[derive(Debug, Component)]
+struct JoystickKnob;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+enum TouchButton {
+    Fire,
+    Brake,
+    Pause,
+}
+
+const JOYSTICK_SIZE: f32 = 160.0;
+const KNOB_SIZE: f32 = 60.0;
+
+fn update_touch_controls(
+    touches: Res<Touches>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut controls: ResMut<TouchControls>,
+    joysticks: Query<(&Node, &GlobalTransform), With<Joystick>>,
+    buttons: Query<(&Node, &GlobalTransform, &TouchButton)>,
+) {
+    if touches.any_just_pressed() {
+        controls.visible = true;
+    } else if keyboard_input.get_just_pressed().next().is_some() {
+        controls.visible = false;
+    }
+
+    controls.joystick = Vec2::ZERO;
+    controls.fire = false;
+    controls.brake = false;
+    if !controls.visible {
+        return;
+    }
+
+    // The UI only tracks the first touch, so the controls check all touches themselves.
+    for touch in touches.iter() {
+        for (node, transform, button) in &buttons {
+            if node.logical_rect(transform).contains(touch.position()) {
+                match button {
+                    TouchButton::Fire => controls.fire = true,
+                    TouchButton::Brake => controls.brake = true,
+                    TouchButton::Pause => (),
+                }
+            }
+        }
+
+        // Touches starting on or near the joystick keep controlling it when dragged away.
+        if let Ok((node, transform)) = joysticks.get_single() {
+            let rect = node.logical_rect(transform);
+            if rect
+                .inflate(rect.width() / 2.0)
+                .contains(touch.start_position())
+            {
+                let offset = (touch.position() - rect.center()) / (rect.width() / 2.0);
+                controls.joystick = Vec2::new(offset.x, -offset.y).clamp_length_max(1.0);
+            }
+        }
+    }
+}
+
+fn pause(
+    touches: Res<Touches>,
+    controls: Res<TouchControls>,
+    buttons: Query<(&Node, &GlobalTransform, &TouchButton)>,
+    game_state: Res<State<GameState>>,
+    mut next_state_game: ResMut<NextState<GameState>>,
+) {
+    if !controls.visible || **game_state != GameState::Running {
+        return;
+    }
+
+    for touch in touches.iter_just_pressed() {
+        for (node, transform, button) in &buttons {
+            if *button == TouchButton::Pause
+                && node.logical_rect(transform).contains(touch.position())
+            {
+                next_state_game.set(GameState::Paused);
+            }
+        }
+    }
+}
+
+fn update_ui(
+    controls: Res<TouchControls>,
+    mut touch_ui: Query<&mut Visibility, With<TouchUi>>,
+    mut knobs: Query<&mut Style, With<JoystickKnob>>,
+) {
+    if !controls.is_changed() {
+        return;
+    }
+
+    for mut visibility in &mut touch_ui {
+        *visibility = if controls.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+    for mut style in &mut knobs {
+        let center = (JOYSTICK_SIZE - KNOB_SIZE) / 2.0;
+        let offset = controls.joystick * (JOYSTICK_SIZE / 2.0);
+        style.left = Val::Px(center + offset.x);
+        style.top = Val::Px(center - offset.y);
+    }
+}
+
+fn setup(mut commands: Commands, controls: Res<TouchControls>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    ..default()
+                },
+                visibility: if controls.visible {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                ..default()
+            },
+            TouchUi,
+            StateScoped(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    circle(
+                        JOYSTICK_SIZE,
+                        Style {
+                            left: Val::Px(40.0),
+                            bottom: Val::Px(40.0),
+                            ..default()
+                        },
+                    ),
+                    Joystick,
+                ))
+                .with_children(|parent| {
+                    let center = Val::Px((JOYSTICK_SIZE - KNOB_SIZE) / 2.0);
+                    parent.spawn((
+                        circle(
+                            KNOB_SIZE,
+                            Style {
+                                left: center,
+                                top: center,
+                                ..default()
+                            },
+                        ),
+                        JoystickKnob,
+                    ));
+                });
+
+            for (button, text, style) in [
+                (
+                    TouchButton::Fire,
+                    "FIRE",
+                    Style {
+                        right: Val::Px(40.0),
+                        bottom: Val::Px(40.0),
+                        ..default()
+                    },
+                ),
+                (
+                    TouchButton::Brake,
+                    "BRAKE",
+                    Style {
+                        right: Val::Px(160.0),
+                        bottom: Val::Px(40.0),
+                        ..default()
+                    },
+                ),
+                (
+                    TouchButton::Pause,
+                    "II",
+                    Style {
+                        right: Val::Px(20.0),
+                        top: Val::Px(20.0),
+                        ..default()
+                    },
+                ),
+            ] {
+                let size = if button == TouchButton::Pause {
+                    50.0
+                } else {
+                    100.0
+                };
+                parent
+                    .spawn((circle(size, style), button))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            text,
+                            TextStyle {
+                                font_size: 20.0,
+                                color: Color::srgba(0.9, 0.9, 0.9, 0.6),
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn circle(size: f32, style: Style) -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Px(size),
+            height: Val::Px(size),
+            border: UiRect::all(Val::Px(2.0)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..style
+        },
+        border_color: BorderColor(Color::srgba(0.9, 0.9, 0.9, 0.3)),
+        border_radius: BorderRadius::MAX,
+        background_color: BackgroundColor(Color::srgba(0.5, 0.5, 0.5, 0.15)),
+        ..default()
+    }
+}
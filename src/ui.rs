@@ -1,17 +1,75 @@
-use bevy::prelude::*;
+use crate::{
+    assets::AudioAssets,
+    gamepad::GamepadInput,
+    mixer::{AudioChannel, ChannelAudioBundle},
+};
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    hierarchy::HierarchyQueryExt,
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+    ui::{RelativeCursorPosition, UiSystem},
+};
 
 pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
 const HOVERED_BUTTON: Color = Color::srgb(0.25, 0.25, 0.25);
 const PRESSED_BUTTON: Color = Color::srgb(0.35, 0.35, 0.35);
+const BORDER: Color = Color::srgb(0.35, 0.35, 0.35);
+const FOCUSED_BORDER: Color = Color::srgb(0.9, 0.9, 0.9);
 
 pub struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, buttons);
+        app.add_event::<TextInputSubmitted>();
+        app.init_resource::<GamepadFocus>();
+        app.add_systems(PreUpdate, gamepad_navigation.after(UiSystem::Focus));
+        app.add_systems(
+            Update,
+            (buttons, focus_borders, click_sounds, sliders, text_inputs),
+        );
     }
 }
 
+/// The button selected with a gamepad.
+#[derive(Debug, Default, Resource)]
+struct GamepadFocus(Option<Entity>);
+
+/// A button pressed with a gamepad, released again in the next frame.
+#[derive(Debug, Component)]
+struct GamepadPressed;
+
+/// A horizontal slider with a value between 0 and 1, set by clicking or dragging.
+#[derive(Debug, Component)]
+pub struct Slider {
+    pub label: String,
+    pub value: f32,
+}
+
+#[derive(Debug, Component)]
+struct SliderFill;
+
+/// Marks widgets that receive all keyboard input while they exist, so it doesn't trigger actions.
+#[derive(Debug, Component)]
+pub struct CapturesKeyboard;
+
+/// A single line text input. While one exists it receives all typed text.
+#[derive(Debug, Component)]
+pub struct TextInput {
+    pub value: String,
+    pub max_len: usize,
+}
+
+/// Sent when enter is pressed in a [`TextInput`].
+#[derive(Debug, Event)]
+pub struct TextInputSubmitted {
+    pub entity: Entity,
+    pub value: String,
+}
+
 fn buttons(
     mut interaction_query: Query<
         (&Interaction, &mut BackgroundColor),
@@ -33,6 +91,350 @@ fn buttons(
     }
 }
 
+/// Moves the [`GamepadFocus`] with the d-pad or left stick and presses the focused button with
+/// the south button (A on Xbox controllers). Sliders are changed with left and right instead.
+fn gamepad_navigation(
+    mut commands: Commands,
+    gamepad: GamepadInput,
+    mut focus: ResMut<GamepadFocus>,
+    mut last_direction: Local<Vec2>,
+    mut buttons: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &InheritedVisibility,
+            &mut Interaction,
+            Option<&mut Slider>,
+        ),
+        With<Button>,
+    >,
+    pressed: Query<Entity, With<GamepadPressed>>,
+    parents: Query<&Parent>,
+    z_indices: Query<&ZIndex>,
+) {
+    for entity in &pressed {
+        commands.entity(entity).remove::<GamepadPressed>();
+        if let Ok((_, _, _, mut interaction, _)) = buttons.get_mut(entity) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    // Only buttons of the top most screen can be selected, e.g. the options above the pause menu.
+    let layer = |entity| match parents
+        .iter_ancestors(entity)
+        .last()
+        .and_then(|root| z_indices.get(root).ok())
+    {
+        Some(ZIndex::Global(z_index)) => *z_index,
+        _ => 0,
+    };
+    let candidates = buttons
+        .iter()
+        .filter(|(_, _, visibility, _, _)| visibility.get())
+        .map(|(entity, transform, ..)| (entity, transform.translation().truncate(), layer(entity)))
+        .collect::<Vec<_>>();
+    let top_layer = candidates.iter().map(|(.., layer)| *layer).max();
+    let candidates = candidates
+        .into_iter()
+        .filter(|(.., layer)| Some(*layer) == top_layer)
+        .map(|(entity, position, _)| (entity, position))
+        .collect::<Vec<_>>();
+
+    if focus
+        .0
+        .is_some_and(|focused| !candidates.iter().any(|(entity, _)| *entity == focused))
+    {
+        focus.0 = None;
+    }
+
+    // Only react when the stick is pushed, not while it is held.
+    let direction = gamepad.direction();
+    let pushed = direction.length() > 0.5 && last_direction.length() <= 0.5;
+    *last_direction = direction;
+
+    if pushed {
+        // UI coordinates point down.
+        let direction = if direction.x.abs() > direction.y.abs() {
+            Vec2::new(direction.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, -direction.y.signum())
+        };
+
+        match focus.0 {
+            None => {
+                focus.0 = candidates
+                    .iter()
+                    .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+                    .map(|(entity, _)| *entity);
+            }
+            Some(focused) => {
+                let (_, position, _, _, slider) = buttons.get_mut(focused).unwrap();
+                match slider {
+                    Some(mut slider) if direction.x != 0.0 => {
+                        slider.value = (slider.value + 0.05 * direction.x).clamp(0.0, 1.0);
+                    }
+                    _ => {
+                        let position = position.translation().truncate();
+                        let next = candidates
+                            .iter()
+                            .filter_map(|(entity, other)| {
+                                let offset = *other - position;
+                                let along = offset.dot(direction);
+                                let across = offset.perp_dot(direction).abs();
+                                (along > 1.0).then_some((*entity, along + 2.0 * across))
+                            })
+                            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+                        if let Some((next, _)) = next {
+                            focus.0 = Some(next);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if gamepad.just_pressed(GamepadButtonType::South) {
+        if let Some(focused) = focus.0 {
+            let (_, _, _, mut interaction, slider) = buttons.get_mut(focused).unwrap();
+            if slider.is_none() {
+                *interaction = Interaction::Pressed;
+                commands.entity(focused).insert(GamepadPressed);
+            }
+        }
+    }
+}
+
+fn focus_borders(
+    focus: Res<GamepadFocus>,
+    mut buttons: Query<(Entity, &mut BorderColor), With<Button>>,
+) {
+    for (entity, mut border_color) in &mut buttons {
+        let color = if focus.0 == Some(entity) {
+            FOCUSED_BORDER
+        } else {
+            BORDER
+        };
+        if border_color.0 != color {
+            border_color.0 = color;
+        }
+    }
+}
+
+fn click_sounds(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    // Not available on the splash and error screens before the audio is loaded.
+    audio_assets: Option<Res<AudioAssets>>,
+) {
+    let Some(audio_assets) = audio_assets else {
+        return;
+    };
+
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.spawn(ChannelAudioBundle::new(
+                AudioChannel::Ui,
+                audio_assets.laser_small_000.clone(),
+                PlaybackSettings {
+                    mode: PlaybackMode::Despawn,
+                    volume: Volume::new(0.3),
+                    speed: 1.5,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+fn sliders(
+    mut sliders: Query<(
+        &Interaction,
+        &RelativeCursorPosition,
+        &mut Slider,
+        &Children,
+    )>,
+    mut fills: Query<&mut Style, With<SliderFill>>,
+    mut texts: Query<&mut Text>,
+) {
+    for (interaction, cursor_position, mut slider, children) in &mut sliders {
+        if *interaction == Interaction::Pressed {
+            if let Some(position) = cursor_position.normalized {
+                // Rounded to whole percents, matching the displayed value.
+                let value = (position.x.clamp(0.0, 1.0) * 100.0).round() / 100.0;
+                if slider.value != value {
+                    slider.value = value;
+                }
+            }
+        }
+
+        if slider.is_changed() {
+            let mut fills = fills.iter_many_mut(children);
+            while let Some(mut style) = fills.fetch_next() {
+                style.width = Val::Percent(slider.value * 100.0);
+            }
+            let mut texts = texts.iter_many_mut(children);
+            while let Some(mut text) = texts.fetch_next() {
+                text.sections[0].value = slider_text(&slider);
+            }
+        }
+    }
+}
+
+fn slider_text(slider: &Slider) -> String {
+    format!("{}: {:.0}%", slider.label, slider.value * 100.0)
+}
+
+fn text_inputs(
+    mut events: EventReader<KeyboardInput>,
+    mut submitted: EventWriter<TextInputSubmitted>,
+    mut text_inputs: Query<(Entity, &mut TextInput, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    let events = events
+        .read()
+        .filter(|event| event.state == ButtonState::Pressed)
+        .collect::<Vec<_>>();
+
+    for (entity, mut text_input, children) in &mut text_inputs {
+        for event in &events {
+            match &event.logical_key {
+                Key::Character(chars) => {
+                    for c in chars.chars().filter(|c| !c.is_control()) {
+                        if text_input.value.chars().count() < text_input.max_len {
+                            text_input.value.push(c);
+                        }
+                    }
+                }
+                Key::Space if text_input.value.chars().count() < text_input.max_len => {
+                    text_input.value.push(' ');
+                }
+                Key::Backspace => {
+                    text_input.value.pop();
+                }
+                Key::Enter => {
+                    submitted.send(TextInputSubmitted {
+                        entity,
+                        value: text_input.value.clone(),
+                    });
+                }
+                _ => (),
+            }
+        }
+
+        if text_input.is_changed() {
+            let mut iter = texts.iter_many_mut(children);
+            if let Some(mut text) = iter.fetch_next() {
+                text.sections[0].value = format!("{}_", text_input.value);
+            }
+        }
+    }
+}
+
+pub fn spawn_text_input_with<C: Component>(
+    parent: &mut ChildBuilder,
+    value: impl Into<String>,
+    max_len: usize,
+    component: C,
+) -> Entity {
+    let value = value.into();
+    parent
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(300.0),
+                    height: Val::Px(60.0),
+                    margin: UiRect::all(Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(BORDER),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            TextInput {
+                value: value.clone(),
+                max_len,
+            },
+            CapturesKeyboard,
+            component,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                format!("{value}_"),
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(0.9, 0.9, 0.9),
+                    ..default()
+                },
+            ));
+        })
+        .id()
+}
+
+pub fn spawn_slider_with<C: Component>(
+    parent: &mut ChildBuilder,
+    label: impl Into<String>,
+    value: f32,
+    component: C,
+) -> Entity {
+    let slider = Slider {
+        label: label.into(),
+        value: value.clamp(0.0, 1.0),
+    };
+    let text = slider_text(&slider);
+    let width = Val::Percent(slider.value * 100.0);
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(300.0),
+                    height: Val::Px(60.0),
+                    margin: UiRect::all(Val::Px(10.0)),
+                    border: UiRect::all(Val::Px(3.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(BORDER),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            RelativeCursorPosition::default(),
+            slider,
+            component,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        width,
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    border_radius: BorderRadius::all(Val::Px(5.0)),
+                    background_color: Color::srgb(0.3, 0.3, 0.45).into(),
+                    ..default()
+                },
+                SliderFill,
+            ));
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font_size: 30.0,
+                    color: Color::srgb(0.9, 0.9, 0.9),
+                    ..default()
+                },
+            ));
+        })
+        .id()
+}
+
 pub fn spawn_button_with<C: Component>(
     parent: &mut ChildBuilder,
     text: impl Into<String>,
@@ -50,7 +452,7 @@ pub fn spawn_button_with<C: Component>(
                     align_items: AlignItems::Center,
                     ..default()
                 },
-                border_color: BorderColor(Color::srgb(0.35, 0.35, 0.35)),
+                border_color: BorderColor(BORDER),
                 border_radius: BorderRadius::all(Val::Px(8.0)),
                 background_color: NORMAL_BUTTON.into(),
                 ..default()
@@ -0,0 +1,133 @@
+use planet_guard::{
+    load_levels, GameMode, GameState, Health, Level, PlayerInput, RunResult, Simulation,
+};
+
+fn level(name: &str) -> Level {
+    let bytes = std::fs::read(format!("assets/levels/{name}.level.ron")).unwrap();
+    Level::from_ron(&bytes).unwrap()
+}
+
+#[test]
+fn level_is_won_when_home_completes_its_orbit() {
+    let mut level = level("easy");
+    level.home_health = Health::new(f32::MAX);
+
+    let mut simulation = Simulation::new(level, GameMode::Single, 1);
+    let steps = simulation.run_until_over(60 * 60).unwrap();
+
+    // The home planet orbits once in 30 seconds.
+    assert_eq!(simulation.state(), GameState::GameWon);
+    assert!((1800..1805).contains(&steps), "won after {steps} steps");
+}
+
+#[test]
+fn stats_stop_when_the_run_is_decided() {
+    let mut level = level("easy");
+    level.home_health = Health::new(f32::MAX);
+
+    let mut simulation = Simulation::new(level, GameMode::Single, 1);
+    simulation.run_until_over(60 * 60).unwrap();
+    let stats = simulation.stats().clone();
+
+    // Still flying on the end screen.
+    for step in 0..60 {
+        simulation.set_input(
+            0,
+            PlayerInput {
+                fire: step % 2 == 0,
+                ..Default::default()
+            },
+        );
+        simulation.step();
+    }
+    assert_eq!(simulation.state(), GameState::GameWon);
+    assert_eq!(simulation.stats(), &stats);
+}
+
+#[test]
+fn home_is_destroyed_after_three_enemy_hits() {
+    let mut level = level("easy");
+    level.home_health = Health::new(3.0 * level.enemy_damage);
+    level.enemy_force_to_home_probability = 1.0;
+
+    let mut simulation = Simulation::new(level, GameMode::Single, 2);
+    simulation.run_until_over(60 * 60).unwrap();
+
+    assert_eq!(simulation.state(), GameState::GameOver);
+    assert!(simulation.home_health().is_none());
+    assert_eq!(simulation.stats().home_damage_taken, 30.0);
+}
+
+/// Plays with scripted input for all players, and checks that the replay ends up the same.
+fn assert_replay_plays_out_the_same(mode: GameMode) -> Simulation {
+    let mut simulation = Simulation::new(level("medium"), mode, 3);
+    for step in 0..1200 {
+        for player in 0..mode.players() {
+            let step = step + player * 50;
+            simulation.set_input(
+                player,
+                PlayerInput {
+                    steer: [0.0, -1.0, 0.5, 1.0][step / 100 % 4],
+                    throttle: if step % 300 < 150 { 1.0 } else { 0.0 },
+                    brake: step % 300 >= 250,
+                    fire: step % 20 < 10,
+                },
+            );
+        }
+        simulation.step();
+    }
+    assert!(simulation.stats().shots_fired > 0);
+
+    let mut replay = Simulation::from_replay(level("medium"), simulation.replay());
+    replay.steps(1200);
+
+    assert_eq!(replay.stats(), simulation.stats());
+    assert_eq!(replay.player_positions(), simulation.player_positions());
+    assert_eq!(
+        replay.home_health().map(|health| health.current()),
+        simulation.home_health().map(|health| health.current())
+    );
+    assert_eq!(replay.versus_wins(), simulation.versus_wins());
+    simulation
+}
+
+#[test]
+fn replay_plays_out_the_same() {
+    assert_replay_plays_out_the_same(GameMode::Single);
+}
+
+#[test]
+fn coop_replay_plays_out_the_same() {
+    let mut simulation = assert_replay_plays_out_the_same(GameMode::Coop);
+    assert_eq!(simulation.player_positions().len(), 2);
+}
+
+#[test]
+fn versus_replay_plays_out_the_same() {
+    let simulation = assert_replay_plays_out_the_same(GameMode::Versus);
+    // Rounds have to end for the replay to cover them.
+    let wins = simulation.versus_wins().unwrap();
+    assert!(wins[0] + wins[1] > 0, "{wins:?}");
+}
+
+#[test]
+fn bot_wins_the_easy_level() {
+    let result = RunResult::play(&level("easy"), GameMode::Single, 4, 60 * 60);
+
+    assert!(result.won);
+    assert!(result.kills > 0);
+}
+
+#[test]
+fn invalid_levels_are_not_loaded() {
+    let dir = std::env::temp_dir().join(format!("planet_guard_levels_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let easy = std::fs::read_to_string("assets/levels/easy.level.ron").unwrap();
+    let invalid = easy.replace("enemy_spawn_interval: 5.0", "enemy_spawn_interval: 0.0");
+    std::fs::write(dir.join("invalid.level.ron"), invalid).unwrap();
+
+    let err = load_levels(&dir).unwrap_err();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(err.contains("invalid.level.ron"), "{err}");
+    assert!(err.contains("enemy_spawn_interval"), "{err}");
+}